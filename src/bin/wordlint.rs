@@ -0,0 +1,74 @@
+//! Checks word lists before they reach the game.
+//!
+//! ```sh
+//! cargo run --bin wordlint                    # checks words.yaml
+//! cargo run --bin wordlint -- packs/*.yaml   # checks word pack files
+//! ```
+//!
+//! Exits with status 1 if any file has errors. Warnings are printed but don't fail.
+
+#[allow(dead_code)]
+#[path = "../views/game/types.rs"]
+mod types;
+#[allow(dead_code)]
+#[path = "../views/game/validation.rs"]
+mod validation;
+
+use std::process::ExitCode;
+use validation::{has_errors, validate_word_list, validate_words_yaml};
+
+fn main() -> ExitCode {
+    let mut paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        paths.push("words.yaml".to_string());
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: error: {}", path, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let diagnostics = if path.to_ascii_lowercase().ends_with(".json") {
+            match serde_json::from_str(&source) {
+                Ok(word_list) => validate_word_list(&word_list),
+                Err(e) => {
+                    eprintln!("{}:{}: error: could not parse word list: {}", path, e.line(), e);
+                    failed = true;
+                    continue;
+                }
+            }
+        } else {
+            validate_words_yaml(&source)
+        };
+
+        for diagnostic in &diagnostics {
+            match diagnostic.line {
+                Some(line) => eprintln!("{}:{}: {}", path, line, severity_and_message(diagnostic)),
+                None => eprintln!("{}: {}", path, severity_and_message(diagnostic)),
+            }
+        }
+        failed |= has_errors(&diagnostics);
+        if diagnostics.is_empty() {
+            println!("{}: ok", path);
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn severity_and_message(diagnostic: &validation::Diagnostic) -> String {
+    match diagnostic.severity {
+        validation::Severity::Error => format!("error: {}", diagnostic.message),
+        validation::Severity::Warning => format!("warning: {}", diagnostic.message),
+    }
+}
@@ -1,6 +1,6 @@
 use dioxus::prelude::*;
 
-use views::Game;
+use views::{Game, RoomHost, RoomJoin, SavedGames, Stats};
 
 /// Define a views module that contains the UI for our app.
 mod views;
@@ -9,12 +9,24 @@ mod views;
 #[cfg(feature = "server")]
 mod server;
 
+/// Live multi-device rooms
+#[cfg(feature = "server")]
+mod rooms;
+
 /// The Route enum is used to define the structure of internal routes in our app.
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
 enum Route {
     #[route("/")]
     Game {},
+    #[route("/host")]
+    RoomHost {},
+    #[route("/join/:code")]
+    RoomJoin { code: String },
+    #[route("/stats")]
+    Stats {},
+    #[route("/games")]
+    SavedGames {},
 }
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
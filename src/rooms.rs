@@ -0,0 +1,228 @@
+use crate::views::{seat_index, GameAction, GameEngine, GameError, GameState, RoomTicket};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Unambiguous characters only - no 0/O or 1/I when read out loud
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 5;
+/// Give up looking for a free code after this many draws, rather than hold every room up
+const MAX_CODE_ATTEMPTS: usize = 32;
+/// Random bytes in a host token or seat secret
+const SECRET_BYTES: usize = 16;
+
+/// Rooms nobody has touched for this long are dropped
+const ROOM_IDLE_TIMEOUT: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// A live room. The authoritative state lives in the watch channel so every
+/// connection is woken up when it changes.
+struct Room {
+    state: watch::Sender<GameState>,
+    host_token: String, // Proves a connection is the device that created the room
+    seats: HashMap<String, String>, // Claimed seat (trimmed, lowercase name) -> its secret
+    last_active: Instant,
+}
+
+static ROOMS: Lazy<Mutex<HashMap<String, Room>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug)]
+pub enum RoomError {
+    NotFound,
+    Rejected(GameError),
+    /// Couldn't draw a code or secret from the OS
+    Entropy(getrandom::Error),
+    /// Every code drawn was already in use
+    NoFreeCode,
+    /// Another phone has already joined under this name
+    SeatTaken(String),
+    /// Nobody by this name is playing in the room
+    UnknownSeat(String),
+}
+
+impl fmt::Display for RoomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoomError::NotFound => write!(f, "Room not found"),
+            RoomError::Rejected(e) => write!(f, "{}", e),
+            RoomError::Entropy(e) => write!(f, "No randomness available: {}", e),
+            RoomError::NoFreeCode => write!(f, "No free room codes, try again later"),
+            RoomError::SeatTaken(name) => {
+                write!(f, "Someone has already joined as {}. Pick your own name from the host's list", name)
+            }
+            RoomError::UnknownSeat(name) => {
+                write!(f, "Nobody called {} is playing. Use the name the host typed in for you", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RoomError {}
+
+/// Who a connection to a room turned out to be, once its token was checked
+#[derive(Clone, PartialEq, Debug)]
+pub enum RoomAccess {
+    /// Drives the game
+    Host,
+    /// Sees this player's card and votes as them
+    Seat(String),
+}
+
+fn random_code() -> Result<String, RoomError> {
+    let mut buf = [0u8; CODE_LENGTH];
+    getrandom::getrandom(&mut buf).map_err(RoomError::Entropy)?;
+    // 256 is a multiple of the alphabet size, so this is uniform
+    Ok(buf
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect())
+}
+
+fn random_secret() -> Result<String, RoomError> {
+    let mut buf = [0u8; SECRET_BYTES];
+    getrandom::getrandom(&mut buf).map_err(RoomError::Entropy)?;
+    Ok(buf.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Normalize a code typed by a player
+fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+/// Seats are matched the way `seat_index` matches names
+fn seat_key(name: &str) -> String {
+    name.trim().to_ascii_lowercase()
+}
+
+/// Create a room with a fresh game, returning its join code and the host's token
+pub fn create_room() -> Result<RoomTicket, RoomError> {
+    let host_token = random_secret()?;
+    let mut rooms = ROOMS.lock().unwrap_or_else(|e| e.into_inner());
+    rooms.retain(|_, room| room.last_active.elapsed() < ROOM_IDLE_TIMEOUT);
+
+    let mut attempts = 0;
+    let code = loop {
+        let code = random_code()?;
+        if !rooms.contains_key(&code) {
+            break code;
+        }
+        attempts += 1;
+        if attempts == MAX_CODE_ATTEMPTS {
+            return Err(RoomError::NoFreeCode);
+        }
+    };
+
+    let (state, _) = watch::channel(GameState::new(code.clone()));
+    rooms.insert(
+        code.clone(),
+        Room { state, host_token: host_token.clone(), seats: HashMap::new(), last_active: Instant::now() },
+    );
+    Ok(RoomTicket { code, host_token })
+}
+
+/// Claim the seat for `name` and return its secret. Each name can only be claimed once,
+/// so nobody else can connect as that player afterwards, and only names the host has seated can be claimed.
+pub fn claim_seat(code: &str, name: &str) -> Result<String, RoomError> {
+    let secret = random_secret()?;
+    let mut rooms = ROOMS.lock().unwrap_or_else(|e| e.into_inner());
+    let room = rooms.get_mut(&normalize_code(code)).ok_or(RoomError::NotFound)?;
+    let key = seat_key(name);
+    if key.is_empty() {
+        return Err(RoomError::Rejected(GameError::EmptyPlayerName(0)));
+    }
+    if seat_index(&room.state.borrow(), name).is_none() {
+        return Err(RoomError::UnknownSeat(name.trim().to_string()));
+    }
+    if room.seats.contains_key(&key) {
+        return Err(RoomError::SeatTaken(name.trim().to_string()));
+    }
+    room.seats.insert(key, secret.clone());
+    room.last_active = Instant::now();
+    Ok(secret)
+}
+
+/// Listen to a room's state as the host (no seat) or as a seated player, if `token` proves it.
+/// A wrong token looks the same as a missing room.
+pub fn subscribe_room(code: &str, seat: Option<&str>, token: &str) -> Option<(RoomAccess, watch::Receiver<GameState>)> {
+    let rooms = ROOMS.lock().unwrap_or_else(|e| e.into_inner());
+    let room = rooms.get(&normalize_code(code))?;
+    let access = match seat {
+        None if room.host_token == token => RoomAccess::Host,
+        Some(name) if room.seats.get(&seat_key(name)).is_some_and(|secret| secret == token) => {
+            RoomAccess::Seat(name.trim().to_string())
+        }
+        _ => return None,
+    };
+    Some((access, room.state.subscribe()))
+}
+
+/// Run an action through the room's engine and publish the new state
+pub fn apply_room_action(code: &str, action: GameAction) -> Result<(), RoomError> {
+    let mut rooms = ROOMS.lock().unwrap_or_else(|e| e.into_inner());
+    let room = rooms.get_mut(&normalize_code(code)).ok_or(RoomError::NotFound)?;
+
+    let engine = GameEngine::new(room.state.borrow().clone());
+    let next = engine.apply(action).map_err(RoomError::Rejected)?;
+    room.state.send_replace(next);
+    room.last_active = Instant::now();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh room with these names typed in on the host's setup screen
+    fn room_with(names: &[&str]) -> RoomTicket {
+        let ticket = create_room().unwrap();
+        apply_room_action(&ticket.code, GameAction::SetPlayerCount(names.len().to_string())).unwrap();
+        for (index, name) in names.iter().enumerate() {
+            apply_room_action(&ticket.code, GameAction::SetPlayerName { index, name: name.to_string() }).unwrap();
+        }
+        ticket
+    }
+
+    #[test]
+    fn only_the_host_token_opens_the_host_connection() {
+        let ticket = create_room().unwrap();
+        let (access, _) = subscribe_room(&ticket.code.to_lowercase(), None, &ticket.host_token).unwrap();
+        assert_eq!(access, RoomAccess::Host);
+        assert!(subscribe_room(&ticket.code, None, "").is_none());
+        assert!(subscribe_room(&ticket.code, None, "guess").is_none());
+        assert!(subscribe_room("NOPE1", None, &ticket.host_token).is_none());
+    }
+
+    #[test]
+    fn seats_need_their_own_secret() {
+        let ticket = room_with(&["Ann", "Bob", "Cat"]);
+        let secret = claim_seat(&ticket.code, " Ann ").unwrap();
+        assert_eq!(secret.len(), SECRET_BYTES * 2);
+        assert!(matches!(claim_seat(&ticket.code, "ANN"), Err(RoomError::SeatTaken(_))));
+
+        let (access, _) = subscribe_room(&ticket.code, Some("ann"), &secret).unwrap();
+        assert_eq!(access, RoomAccess::Seat("ann".to_string()));
+        // Nobody else's secret, and not the host's token, lets a phone sit in Ann's seat
+        let bob = claim_seat(&ticket.code, "Bob").unwrap();
+        assert!(subscribe_room(&ticket.code, Some("Ann"), &bob).is_none());
+        assert!(subscribe_room(&ticket.code, Some("Ann"), &ticket.host_token).is_none());
+        assert!(subscribe_room(&ticket.code, Some("Cat"), "").is_none());
+        // A seat's secret doesn't make a phone the host either
+        assert!(subscribe_room(&ticket.code, None, &secret).is_none());
+    }
+
+    #[test]
+    fn only_seated_names_can_be_claimed() {
+        let ticket = room_with(&["Ann", "Bob", "Cat"]);
+        assert!(matches!(claim_seat(&ticket.code, "Zed"), Err(RoomError::UnknownSeat(_))));
+        assert!(claim_seat(&ticket.code, "cat").is_ok());
+
+        // Once the game is under way the seated players are the ones that count
+        apply_room_action(&ticket.code, GameAction::SetSeed(Some(7))).unwrap();
+        apply_room_action(&ticket.code, GameAction::StartGame).unwrap();
+        assert!(claim_seat(&ticket.code, "Bob").is_ok());
+        assert!(matches!(claim_seat(&ticket.code, ""), Err(RoomError::Rejected(_))));
+        assert!(matches!(claim_seat("NOPE1", "Ann"), Err(RoomError::NotFound)));
+    }
+}
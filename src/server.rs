@@ -1,71 +1,371 @@
+use std::fmt;
 use std::fs;
+use std::io::{self, Write};
 use std::path::PathBuf;
+use uuid::Uuid;
 
-/// Get the directory for storing game saves
-fn get_saves_dir() -> PathBuf {
-    let mut path = PathBuf::from("game_saves");
-    if !path.exists() {
-        let _ = fs::create_dir_all(&path);
+/// Environment variable that overrides where game saves are stored
+pub const SAVES_DIR_ENV: &str = "ULTIMATE_IMPOSTER_SAVES_DIR";
+
+/// Used when `SAVES_DIR_ENV` isn't set
+const DEFAULT_SAVES_DIR: &str = "game_saves";
+
+#[derive(Debug)]
+pub enum SaveError {
+    /// The session ID isn't a UUID, so it can't be used as a file name
+    InvalidSessionId(String),
+    /// No save exists for this session
+    NotFound(String),
+    /// The save belongs to another browser, so this one can't overwrite or delete it
+    NotOwner(String),
+    /// Reading or writing the save failed
+    Io(io::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::InvalidSessionId(id) => write!(f, "Invalid session ID: {:?}", id),
+            SaveError::NotFound(id) => write!(f, "Game save not found: {}", id),
+            SaveError::NotOwner(id) => write!(f, "Game save {} was made by another browser", id),
+            SaveError::Io(e) => write!(f, "Game save I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            _ => None,
+        }
     }
-    path
 }
 
-/// Save game state to disk with session ID
-pub fn save_game_to_disk(session_id: &str, game_state_json: &str) -> Result<(), String> {
-    let saves_dir = get_saves_dir();
-    let file_path = saves_dir.join(format!("{}.json", session_id));
-    
-    fs::write(&file_path, game_state_json)
-        .map_err(|e| format!("Failed to save game: {}", e))?;
-    
-    Ok(())
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
 }
 
-/// Load game state from disk with session ID
-pub fn load_game_from_disk(session_id: &str) -> Result<String, String> {
-    let saves_dir = get_saves_dir();
-    let file_path = saves_dir.join(format!("{}.json", session_id));
-    
-    if !file_path.exists() {
-        return Err("Game save not found".to_string());
-    }
-    
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to load game: {}", e))
+/// Session IDs come from the client, so only accept real UUIDs.
+/// Returns the canonical form, which is also the file stem.
+fn validate_session_id(session_id: &str) -> Result<String, SaveError> {
+    Uuid::parse_str(session_id)
+        .map(|id| id.hyphenated().to_string())
+        .map_err(|_| SaveError::InvalidSessionId(session_id.to_string()))
 }
 
-/// List all saved game sessions
-pub fn list_saved_games() -> Result<Vec<String>, String> {
-    let saves_dir = get_saves_dir();
-    
-    let entries = fs::read_dir(&saves_dir)
-        .map_err(|e| format!("Failed to read saves directory: {}", e))?;
-    
-    let mut sessions = Vec::new();
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if let Some(filename) = entry.file_name().to_str() {
-                if filename.ends_with(".json") {
-                    let session_id = filename.trim_end_matches(".json");
-                    sessions.push(session_id.to_string());
-                }
+/// Directory of JSON game saves, one file per session, each next to a file naming the
+/// browser that first saved it
+#[derive(Clone, Debug)]
+pub struct SaveStore {
+    root: PathBuf,
+}
+
+impl SaveStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        SaveStore { root: root.into() }
+    }
+
+    /// Store rooted at `$ULTIMATE_IMPOSTER_SAVES_DIR`, or `game_saves` if unset
+    pub fn from_env() -> Self {
+        let root = std::env::var_os(SAVES_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_SAVES_DIR));
+        SaveStore::new(root)
+    }
+
+    #[cfg(test)]
+    pub fn root(&self) -> &std::path::Path {
+        &self.root
+    }
+
+    fn save_path(&self, session_id: &str) -> Result<PathBuf, SaveError> {
+        let id = validate_session_id(session_id)?;
+        Ok(self.root.join(format!("{}.json", id)))
+    }
+
+    fn owner_path(&self, session_id: &str) -> Result<PathBuf, SaveError> {
+        let id = validate_session_id(session_id)?;
+        Ok(self.root.join(format!("{}.owner", id)))
+    }
+
+    /// Record `owner` as the browser a session belongs to, unless another browser saved it first
+    pub fn claim(&self, session_id: &str, owner: &str) -> Result<(), SaveError> {
+        let owner_path = self.owner_path(session_id)?;
+        if owner.is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.root)?;
+        match fs::OpenOptions::new().write(true).create_new(true).open(&owner_path) {
+            Ok(mut file) => Ok(file.write_all(owner.as_bytes())?),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The browser recorded as a session's owner, `None` for saves from before owners were recorded
+    fn recorded_owner(&self, session_id: &str) -> Result<Option<String>, SaveError> {
+        match fs::read_to_string(self.owner_path(session_id)?) {
+            Ok(recorded) => Ok(Some(recorded)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save game state for `owner`, claiming the session if nobody has yet.
+    /// A session another browser saved first is left as it is.
+    pub fn save_as(&self, session_id: &str, game_state_json: &str, owner: &str) -> Result<(), SaveError> {
+        self.claim(session_id, owner)?;
+        match self.recorded_owner(session_id)? {
+            Some(recorded) if recorded != owner => Err(SaveError::NotOwner(session_id.to_string())),
+            _ => self.save(session_id, game_state_json),
+        }
+    }
+
+    /// Save game state, replacing any previous save atomically
+    pub fn save(&self, session_id: &str, game_state_json: &str) -> Result<(), SaveError> {
+        let file_path = self.save_path(session_id)?;
+        fs::create_dir_all(&self.root)?;
+
+        // Write to a temp file in the same directory, then rename over the save.
+        // A crash mid-write leaves the old save intact instead of a truncated one.
+        let temp_path = file_path.with_extension(format!("json.{}.tmp", Uuid::new_v4().simple()));
+        let result = (|| {
+            let mut file = fs::File::create(&temp_path)?;
+            file.write_all(game_state_json.as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temp_path, &file_path)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        Ok(result?)
+    }
+
+    /// Load game state for a session
+    pub fn load(&self, session_id: &str) -> Result<String, SaveError> {
+        let file_path = self.save_path(session_id)?;
+        fs::read_to_string(&file_path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => SaveError::NotFound(session_id.to_string()),
+            _ => SaveError::Io(e),
+        })
+    }
+
+    /// Delete a saved game, if `owner` is the browser that first saved it.
+    /// Saves from before owners were recorded can't be deleted from here.
+    pub fn delete(&self, session_id: &str, owner: &str) -> Result<(), SaveError> {
+        let file_path = self.save_path(session_id)?;
+        let owner_path = self.owner_path(session_id)?;
+        if !file_path.exists() {
+            return Err(SaveError::NotFound(session_id.to_string()));
+        }
+        if self.recorded_owner(session_id)?.as_deref() != Some(owner) {
+            return Err(SaveError::NotOwner(session_id.to_string()));
+        }
+        fs::remove_file(&file_path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => SaveError::NotFound(session_id.to_string()),
+            _ => SaveError::Io(e),
+        })?;
+        let _ = fs::remove_file(&owner_path);
+        Ok(())
+    }
+
+    /// Sessions `owner` first saved, sorted. Nobody owns a save from before owners were recorded.
+    pub fn list(&self, owner: &str) -> Result<Vec<String>, SaveError> {
+        if owner.is_empty() {
+            return Ok(Vec::new());
+        }
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut sessions = Vec::new();
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(id) = file_name
+                .to_str()
+                .and_then(|f| f.strip_suffix(".owner"))
+                .and_then(|stem| validate_session_id(stem).ok())
+            else {
+                continue;
+            };
+            if self.recorded_owner(&id)?.as_deref() == Some(owner) && self.save_path(&id)?.exists() {
+                sessions.push(id);
             }
         }
+        sessions.sort();
+        Ok(sessions)
     }
-    
-    Ok(sessions)
 }
 
-/// Delete a saved game
-pub fn delete_saved_game(session_id: &str) -> Result<(), String> {
-    let saves_dir = get_saves_dir();
-    let file_path = saves_dir.join(format!("{}.json", session_id));
-    
-    if !file_path.exists() {
-        return Err("Game save not found".to_string());
-    }
-    
-    fs::remove_file(&file_path)
-        .map_err(|e| format!("Failed to delete game: {}", e))
+/// Save game state to disk with session ID, unless another browser saved it first
+pub fn save_game_to_disk(session_id: &str, game_state_json: &str, owner: &str) -> Result<(), SaveError> {
+    SaveStore::from_env().save_as(session_id, game_state_json, owner)
 }
 
+/// Load game state from disk with session ID
+pub fn load_game_from_disk(session_id: &str) -> Result<String, SaveError> {
+    SaveStore::from_env().load(session_id)
+}
+
+/// Sessions `owner` first saved
+pub fn list_saved_games(owner: &str) -> Result<Vec<String>, SaveError> {
+    SaveStore::from_env().list(owner)
+}
+
+/// Delete a saved game, if `owner` first saved it
+pub fn delete_saved_game(session_id: &str, owner: &str) -> Result<(), SaveError> {
+    SaveStore::from_env().delete(session_id, owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store in a fresh directory under the system temp dir
+    fn temp_store() -> SaveStore {
+        SaveStore::new(std::env::temp_dir().join(format!("ultimate-imposter-test-{}", Uuid::new_v4())))
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let store = temp_store();
+        let id = Uuid::new_v4().to_string();
+
+        store.save(&id, r#"{"round_number":1}"#).unwrap();
+        assert_eq!(store.load(&id).unwrap(), r#"{"round_number":1}"#);
+
+        store.save(&id, r#"{"round_number":2}"#).unwrap();
+        assert_eq!(store.load(&id).unwrap(), r#"{"round_number":2}"#);
+
+        let _ = fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn rejects_path_traversal() {
+        let store = temp_store();
+        for id in ["../escape", "../../etc/passwd", "a/b", "", "not-a-uuid", "..\\escape"] {
+            assert!(matches!(store.save(id, "{}"), Err(SaveError::InvalidSessionId(_))), "{}", id);
+            assert!(matches!(store.load(id), Err(SaveError::InvalidSessionId(_))), "{}", id);
+            assert!(matches!(store.delete(id, "me"), Err(SaveError::InvalidSessionId(_))), "{}", id);
+            assert!(matches!(store.claim(id, "me"), Err(SaveError::InvalidSessionId(_))), "{}", id);
+        }
+        assert!(!store.root().exists());
+    }
+
+    #[test]
+    fn save_leaves_no_temp_files() {
+        let store = temp_store();
+        let id = Uuid::new_v4().to_string();
+        store.save(&id, "{}").unwrap();
+
+        let files: Vec<_> = fs::read_dir(store.root())
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(files, vec![format!("{}.json", id)]);
+
+        let _ = fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn interrupted_write_keeps_previous_save() {
+        let store = temp_store();
+        let id = Uuid::new_v4().to_string();
+        store.save(&id, "old").unwrap();
+
+        // What a crash halfway through the next save leaves behind
+        fs::write(store.root().join(format!("{}.json.deadbeef.tmp", id)), "ne").unwrap();
+
+        assert_eq!(store.load(&id).unwrap(), "old");
+        let _ = fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn unwritable_root_is_io_error() {
+        let store = temp_store();
+        fs::write(store.root(), "not a directory").unwrap();
+        let id = Uuid::new_v4().to_string();
+
+        assert!(matches!(store.save(&id, "{}"), Err(SaveError::Io(_))));
+        let _ = fs::remove_file(store.root());
+    }
+
+    #[test]
+    fn missing_save_is_not_found() {
+        let store = temp_store();
+        let id = Uuid::new_v4().to_string();
+        assert!(matches!(store.load(&id), Err(SaveError::NotFound(_))));
+        assert!(matches!(store.delete(&id, "me"), Err(SaveError::NotFound(_))));
+    }
+
+    #[test]
+    fn only_the_first_browser_to_save_can_overwrite() {
+        let store = temp_store();
+        let id = Uuid::new_v4().to_string();
+        store.save_as(&id, r#"{"round_number":1}"#, "me").unwrap();
+        assert!(matches!(store.save_as(&id, r#"{"round_number":9}"#, "you"), Err(SaveError::NotOwner(_))));
+        assert!(matches!(store.save_as(&id, r#"{"round_number":9}"#, ""), Err(SaveError::NotOwner(_))));
+        assert_eq!(store.load(&id).unwrap(), r#"{"round_number":1}"#);
+
+        store.save_as(&id, r#"{"round_number":2}"#, "me").unwrap();
+        assert_eq!(store.load(&id).unwrap(), r#"{"round_number":2}"#);
+        let _ = fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn only_the_first_browser_to_save_can_delete() {
+        let store = temp_store();
+        let id = Uuid::new_v4().to_string();
+        store.save_as(&id, "{}", "me").unwrap();
+        // Someone else carrying the game on doesn't take it over
+        assert!(store.save_as(&id, "{}", "you").is_err());
+
+        assert!(matches!(store.delete(&id, "you"), Err(SaveError::NotOwner(_))));
+        assert!(matches!(store.delete(&id, ""), Err(SaveError::NotOwner(_))));
+        assert_eq!(store.load(&id).unwrap(), "{}");
+        store.delete(&id, "me").unwrap();
+        assert!(matches!(store.load(&id), Err(SaveError::NotFound(_))));
+
+        // Nobody owns a save from before owners were recorded
+        store.save(&id, "{}").unwrap();
+        assert!(matches!(store.delete(&id, "me"), Err(SaveError::NotOwner(_))));
+        let _ = fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn lists_only_the_owners_saves() {
+        let store = temp_store();
+        assert_eq!(store.list("me").unwrap(), Vec::<String>::new());
+        let mut mine = vec![Uuid::new_v4().to_string(), Uuid::new_v4().to_string()];
+        for id in &mine {
+            store.save_as(id, "{}", "me").unwrap();
+        }
+        store.save_as(&Uuid::new_v4().to_string(), "{}", "you").unwrap();
+        // Saves from before owners were recorded, and stray files, belong to nobody
+        store.save(&Uuid::new_v4().to_string(), "{}").unwrap();
+        fs::write(store.root().join("notes.owner"), "me").unwrap();
+
+        mine.sort();
+        assert_eq!(store.list("me").unwrap(), mine);
+        assert_eq!(store.list("").unwrap(), Vec::<String>::new());
+
+        store.delete(&mine[0], "me").unwrap();
+        assert_eq!(store.list("me").unwrap(), mine[1..].to_vec());
+        let _ = fs::remove_dir_all(store.root());
+    }
+
+    #[test]
+    fn session_ids_are_canonicalized() {
+        let store = temp_store();
+        let id = Uuid::new_v4();
+        store.save(&id.to_string().to_uppercase(), "{}").unwrap();
+        assert_eq!(store.load(&id.to_string()).unwrap(), "{}");
+        let _ = fs::remove_dir_all(store.root());
+    }
+}
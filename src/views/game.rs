@@ -1,12 +1,37 @@
 // Game module structure
 mod types;
+mod rng;
 mod utils;
+mod validation;
+mod guess;
+mod clock;
+mod history;
+mod migrations;
+mod stats;
+mod rosters;
+mod saved_games;
+mod transfer;
+mod word_packs;
+mod engine;
 mod persistence;
+mod room;
 mod components;
 
 // Re-export types for use in components
 pub use types::*;
+pub use engine::*;
 pub use persistence::*;
+#[cfg(feature = "server")]
+pub use room::*;
+pub use clock::*;
+pub use history::*;
+pub use migrations::*;
+pub use stats::*;
+pub use rosters::*;
+pub use saved_games::*;
+pub use transfer::*;
+pub use word_packs::*;
+pub use components::{RoomHost, RoomJoin, SavedGames, Stats};
 // utils is used internally by components
 
 use dioxus::prelude::*;
@@ -14,25 +39,55 @@ use components::*;
 
 const _GAME_CSS: Asset = asset!("/assets/styling/game.css");
 
+/// Run an action through the engine and store the result.
+/// Rejected actions are logged and leave the state untouched.
+///
+/// Inside a room host the action is forwarded to the server instead, which
+/// owns the authoritative state and pushes the result back.
+pub fn dispatch(mut state: Signal<GameState>, action: GameAction) {
+    if let Some(room) = try_consume_context::<Coroutine<GameAction>>() {
+        room.send(action);
+        return;
+    }
+
+    let engine = GameEngine::new(state());
+    match engine.apply(action) {
+        Ok(next) => state.set(next),
+        Err(e) => dioxus::logger::tracing::warn!("Ignoring action: {}", e),
+    }
+}
+
+/// Leave the current game in the saved games list and set up the next one in a new session.
+///
+/// A room's game belongs to the server, so there the room just goes back to setup.
+pub fn start_new_game(mut state: Signal<GameState>) {
+    if try_consume_context::<Coroutine<GameAction>>().is_some() {
+        dispatch(state, GameAction::NewGame);
+        return;
+    }
+
+    match GameEngine::new(state()).apply(GameAction::NewGame) {
+        Ok(next) => {
+            let session_id = generate_session_id();
+            save_session_id(&session_id);
+            state.set(next_game_session(next, session_id));
+        }
+        Err(e) => dioxus::logger::tracing::warn!("Ignoring action: {}", e),
+    }
+}
+
 /// Main Game component
 #[component]
 pub fn Game() -> Element {
     // Initialize game state - load from localStorage if available
-    let mut session_id = use_signal(|| String::new());
-    let mut game_screen = use_signal(|| GameScreen::Setup);
-    let mut players = use_signal(|| Vec::<Player>::new());
-    let mut player_count_input = use_signal(|| String::from("3"));
-    let mut player_names = use_signal(|| Vec::<String>::new());
-    let mut round_number = use_signal(|| 1);
-    let mut cards = use_signal(|| Vec::<GameCard>::new());
-    let mut imposter_index = use_signal(|| 0usize);
-    let mut current_category = use_signal(|| None::<(String, String)>);
-    let mut selected_category_index = use_signal(|| None::<usize>);
-    let mut hide_imposter_identity = use_signal(|| false);
-    let mut current_round_words = use_signal(|| None::<(String, String)>);
-    let mut starting_player_index = use_signal(|| 0usize);
+    let mut state = use_signal(|| GameState::new(String::new()));
     let mut initialized = use_signal(|| false);
-    
+    // Why the saved game couldn't be picked up again, until the player dismisses it
+    let mut restore_failure = use_signal(|| None::<String>);
+
+    // Pick up any word packs the server offers
+    use_future(refresh_server_word_packs);
+
     // Initialize once on mount
     use_effect(move || {
         if !initialized() {
@@ -42,139 +97,150 @@ pub fn Game() -> Element {
                 save_session_id(&id);
                 id
             });
-            
-            session_id.set(sid.clone());
-            
+
             // Try to load saved game state for this session
-            if let Some(saved_state) = load_game_state(&sid) {
-                game_screen.set(saved_state.game_screen);
-                players.set(saved_state.players);
-                player_count_input.set(saved_state.player_count_input);
-                player_names.set(saved_state.player_names);
-                round_number.set(saved_state.round_number);
-                cards.set(saved_state.cards);
-                imposter_index.set(saved_state.imposter_index);
-                current_category.set(saved_state.current_category);
-                selected_category_index.set(saved_state.selected_category_index);
-                hide_imposter_identity.set(saved_state.hide_imposter_identity);
-                current_round_words.set(saved_state.current_round_words);
-                starting_player_index.set(saved_state.starting_player_index);
+            match load_game_state(&sid) {
+                Ok(Some(saved_state)) => {
+                    state.set(GameState { session_id: sid, ..saved_state });
+                    initialized.set(true);
+                }
+                Ok(None) => {
+                    // Nothing in localStorage - fall back to the server's copy
+                    state.set(GameState::new(sid.clone()));
+                    spawn(async move {
+                        match load_game_state_from_disk(&sid).await {
+                            Ok(Some(saved_state)) => state.set(GameState { session_id: sid, ..saved_state }),
+                            Ok(None) => {}
+                            Err(e) => start_fresh_session(state, restore_failure, &sid, e),
+                        }
+                        initialized.set(true);
+                    });
+                }
+                Err(e) => {
+                    start_fresh_session(state, restore_failure, &sid, e);
+                    initialized.set(true);
+                }
             }
-            
-            initialized.set(true);
         }
     });
-    
+
     // Auto-save game state whenever it changes (but only after initialization)
     use_effect(move || {
-        if initialized() && !session_id().is_empty() {
-            let state = GameState {
-                session_id: session_id(),
-                game_screen: game_screen(),
-                players: players(),
-                player_count_input: player_count_input(),
-                player_names: player_names(),
-                round_number: round_number(),
-                cards: cards(),
-                imposter_index: imposter_index(),
-                current_category: current_category(),
-                selected_category_index: selected_category_index(),
-                hide_imposter_identity: hide_imposter_identity(),
-                current_round_words: current_round_words(),
-                starting_player_index: starting_player_index(),
-            };
-            save_game_state(&state);
+        let current = state();
+        if initialized() && !current.session_id.is_empty() {
+            save_game_state(&current);
+            save_player_stats(&current);
         }
     });
-    
+
     rsx! {
         document::Stylesheet { href: _GAME_CSS }
         div { class: "game-container",
-            match game_screen() {
-                GameScreen::Setup => rsx! {
-                    SetupScreen {
-                        player_count_input,
-                        player_names,
-                        players,
-                        game_screen,
-                        round_number,
-                        starting_player_index,
+            if let Some(reason) = restore_failure() {
+                div { class: "restore-notice",
+                    p {
+                        strong { "⚠️ Could not restore your saved game. " }
+                        "{reason}. It has been kept as it was, and a new game has been started."
                     }
-                },
-                GameScreen::CategorySelection => rsx! {
-                    CategorySelectionScreen {
-                        game_screen,
-                        selected_category_index,
-                    }
-                },
-                GameScreen::CategoryReveal { category_name, category_icon } => rsx! {
-                    CategoryRevealScreen {
-                        category_name,
-                        category_icon,
-                        game_screen,
-                        hide_imposter_identity,
-                    }
-                },
-                GameScreen::CardView { current_player_index } => rsx! {
-                    CardViewScreen {
-                        current_player_index,
-                        players,
-                        cards,
-                        imposter_index,
-                        game_screen,
-                        current_category,
-                        selected_category_index,
-                        hide_imposter_identity,
-                        current_round_words,
-                        starting_player_index,
-                    }
-                },
-                GameScreen::Voting => rsx! {
-                    VotingScreen {
-                        players,
-                        game_screen,
-                        imposter_index,
-                        cards,
-                        current_category,
-                        starting_player_index,
-                    }
-                },
-                GameScreen::Elimination { eliminated_index, was_imposter } => rsx! {
-                    EliminationScreen {
-                        players,
-                        eliminated_index,
-                        was_imposter,
-                        game_screen,
-                        round_number,
-                        cards,
-                        imposter_index,
-                    }
-                },
-                GameScreen::RoundEnd { imposter_found, game_over } => rsx! {
-                    RoundEndScreen {
-                        players,
-                        imposter_found,
-                        game_over,
-                        game_screen,
-                        round_number,
-                        cards,
-                        imposter_index,
-                        current_round_words,
-                        starting_player_index,
-                    }
-                },
-                GameScreen::GameScore => rsx! {
-                    GameScoreScreen {
-                        players,
-                        round_number,
-                        game_screen,
-                        cards,
-                        imposter_index,
-                        starting_player_index,
-                    }
-                },
+                    button { onclick: move |_| restore_failure.set(None), "OK" }
+                }
+            }
+            GameScreens { state }
+            // The setup screen links here already; elsewhere this is how a game moves to another phone mid-evening
+            if state().game_screen != GameScreen::Setup {
+                div { class: "room-links",
+                    Link { to: "/games", class: "room-link", "💾 Saved games · 📤 Move to another device" }
+                }
             }
         }
     }
 }
 
+/// A save that can't be restored is left untouched, in case a later version can read it,
+/// and play carries on in a new session with a notice saying why
+fn start_fresh_session(
+    mut state: Signal<GameState>,
+    mut restore_failure: Signal<Option<String>>,
+    session_id: &str,
+    error: RestoreError,
+) {
+    dioxus::logger::tracing::warn!("Could not restore session {}: {}", session_id, error);
+    restore_failure.set(Some(error.to_string()));
+    let fresh = generate_session_id();
+    save_session_id(&fresh);
+    state.set(GameState::new(fresh));
+}
+
+/// Renders whichever screen the game is on.
+/// A room host skips the pass-the-device card flow since every player has their own phone.
+#[component]
+pub fn GameScreens(state: Signal<GameState>, #[props(default)] room_host: bool) -> Element {
+    rsx! {
+        match state().game_screen {
+            GameScreen::Setup => rsx! {
+                SetupScreen { state }
+            },
+            GameScreen::CategorySelection => rsx! {
+                CategorySelectionScreen { state, room_host }
+            },
+            GameScreen::CategoryReveal { category_name, category_icon } => rsx! {
+                CategoryRevealScreen {
+                    category_name,
+                    category_icon,
+                    state,
+                }
+            },
+            GameScreen::CardView { .. } if room_host => rsx! {
+                RoomCardsDealtScreen { state }
+            },
+            // Keyed so an auto-advanced card starts face down
+            GameScreen::CardView { current_player_index } => rsx! {
+                CardViewScreen {
+                    key: "{current_player_index}",
+                    current_player_index,
+                    state,
+                }
+            },
+            GameScreen::Voting => rsx! {
+                VotingScreen { state }
+            },
+            GameScreen::Ballot => rsx! {
+                BallotScreen { key: "{state().vote_tallies.len()}", state, room_host }
+            },
+            GameScreen::Elimination { eliminated_index, was_imposter, was_mr_white } => rsx! {
+                EliminationScreen {
+                    eliminated_index,
+                    was_imposter,
+                    was_mr_white,
+                    state,
+                }
+            },
+            GameScreen::MrWhiteGuess { player_index } => rsx! {
+                MrWhiteGuessScreen {
+                    player_index,
+                    state,
+                }
+            },
+            GameScreen::ImposterGuess { player_index } => rsx! {
+                ImposterGuessScreen {
+                    player_index,
+                    state,
+                }
+            },
+            GameScreen::RoundEnd { imposter_found, game_over, stolen_by } => rsx! {
+                RoundEndScreen {
+                    imposter_found,
+                    game_over,
+                    stolen_by,
+                    state,
+                }
+            },
+            GameScreen::GameScore => rsx! {
+                GameScoreScreen { state }
+            },
+            GameScreen::History => rsx! {
+                HistoryScreen { state }
+            },
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// ============================================================================
+// Wall clock for phase timers - the engine only ever sees the times passed in
+// ============================================================================
+
+/// Milliseconds since the Unix epoch on this device
+pub fn now_ms() -> i64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as i64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
+
+/// Wait about `ms` milliseconds without blocking the UI
+pub async fn sleep_ms(ms: u32) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        gloo_timers::future::TimeoutFuture::new(ms).await;
+    }
+
+    // The server, desktop and mobile apps all run inside tokio, so its timer does the waiting
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(ms.into())).await;
+    }
+}
@@ -0,0 +1,136 @@
+use dioxus::prelude::*;
+use crate::views::game::{dispatch, types::*, vote_candidates, GameAction, PhaseTimerBar};
+use std::cmp::Reverse;
+
+/// Secret vote. On a shared device the players vote one at a time; in a room
+/// everyone votes on their own phone and the host just watches the ballots come in.
+#[component]
+pub fn BallotScreen(state: Signal<GameState>, room_host: bool) -> Element {
+    let current = state();
+    let mut card_revealed = use_signal(|| false);
+
+    // Vote in the same rotated order as the discussion
+    let total_players = current.players.len();
+    let start_idx = current.starting_player_index % total_players.max(1);
+    let voters: Vec<usize> = (0..total_players)
+        .map(|i| (start_idx + i) % total_players)
+        .filter(|&i| !current.players[i].is_eliminated)
+        .collect();
+    let next_voter = voters.iter().copied().find(|i| !current.ballots.contains_key(i));
+    let candidates = vote_candidates(&current);
+    let tied_names = current
+        .revote_candidates
+        .iter()
+        .map(|&i| current.players[i].name.clone())
+        .collect::<Vec<_>>()
+        .join(" and ");
+
+    rsx! {
+        div { class: "voting-screen",
+            h1 { "🗳️ Secret Vote" }
+
+            PhaseTimerBar { state }
+
+            if !current.revote_candidates.is_empty() {
+                p { class: "ballot-revote", "⚖️ It's a tie! Vote again between {tied_names}." }
+            }
+
+            if room_host {
+                div { class: "voting-instructions",
+                    p { "📱 Everyone votes on their own phone." }
+                    p { class: "hint", "{current.ballots.len()} of {voters.len()} votes are in" }
+                }
+                div { class: "players-voting-list",
+                    for &i in voters.iter() {
+                        div { class: "player-voting-card",
+                            div { class: "player-info",
+                                h3 { "{current.players[i].name}" }
+                            }
+                            span { class: "ballot-status",
+                                if current.ballots.contains_key(&i) { "✅ Voted" } else { "⏳ Thinking..." }
+                            }
+                        }
+                    }
+                }
+            } else if let Some(voter) = next_voter {
+                if !card_revealed() {
+                    div { class: "player-ready-screen",
+                        h2 { "Pass device to:" }
+                        h1 { class: "player-name", "{current.players[voter].name}" }
+                        p { class: "instruction", "⚠️ Vote where nobody else can see!" }
+                        button {
+                            class: "reveal-btn",
+                            onclick: move |_| card_revealed.set(true),
+                            "Cast My Vote"
+                        }
+                    }
+                } else {
+                    div { class: "voting-instructions",
+                        p { "{current.players[voter].name}, who should be evicted?" }
+                    }
+                    div { class: "players-voting-list",
+                        for &target in candidates.iter().filter(|&&i| i != voter) {
+                            div { class: "player-voting-card",
+                                div { class: "player-info",
+                                    h3 { "{current.players[target].name}" }
+                                }
+                                button {
+                                    class: "evict-btn",
+                                    onclick: move |_| {
+                                        card_revealed.set(false);
+                                        dispatch(state, GameAction::CastVote { voter, target });
+                                    },
+                                    "Vote"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "voting-footer",
+                button {
+                    class: "restart-round-btn",
+                    onclick: move |_| {
+                        card_revealed.set(false);
+                        dispatch(state, GameAction::CancelBallot);
+                    },
+                    "← Back to Discussion"
+                }
+            }
+        }
+    }
+}
+
+/// Who voted for whom in a counted ballot, and what came of it
+#[component]
+pub fn VoteBreakdown(tally: VoteTally, names: Vec<String>) -> Element {
+    let name = |i: usize| names.get(i).cloned().unwrap_or_default();
+    let mut rows: Vec<(usize, Vec<String>)> = Vec::new();
+    for (&voter, &target) in tally.ballots.iter() {
+        match rows.iter_mut().find(|(t, _)| *t == target) {
+            Some((_, voters)) => voters.push(name(voter)),
+            None => rows.push((target, vec![name(voter)])),
+        }
+    }
+    rows.sort_by_key(|(_, voters)| Reverse(voters.len()));
+    let rows: Vec<String> = rows
+        .into_iter()
+        .map(|(target, voters)| format!("{}: {} ({})", name(target), voters.len(), voters.join(", ")))
+        .collect();
+    let outcome = match &tally.outcome {
+        VoteOutcome::Evicted(evicted) => format!("{} was voted out", name(*evicted)),
+        VoteOutcome::Revote { .. } => "Tied - revote".to_string(),
+        VoteOutcome::NoEviction { .. } => "Tied - nobody was evicted".to_string(),
+        VoteOutcome::RandomEviction { evicted, .. } => format!("Tied - {} was drawn at random", name(*evicted)),
+    };
+
+    rsx! {
+        div { class: "vote-breakdown",
+            for row in rows {
+                p { "{row}" }
+            }
+            p { class: "vote-outcome", "{outcome}" }
+        }
+    }
+}
@@ -1,53 +1,17 @@
 use dioxus::prelude::*;
-use crate::views::game::{types::*, utils::generate_cards_for_category};
+use crate::views::game::{dispatch, types::*, GameAction, PhaseTimerBar};
 
 /// Screen where players view their cards one by one
 #[component]
 pub fn CardViewScreen(
     current_player_index: usize,
-    players: Signal<Vec<Player>>,
-    mut cards: Signal<Vec<GameCard>>,
-    mut imposter_index: Signal<usize>,
-    mut game_screen: Signal<GameScreen>,
-    mut current_category: Signal<Option<(String, String)>>,
-    selected_category_index: Signal<Option<usize>>,
-    hide_imposter_identity: Signal<bool>,
-    mut current_round_words: Signal<Option<(String, String)>>,
-    mut starting_player_index: Signal<usize>,
+    state: Signal<GameState>,
 ) -> Element {
-    // Initialize cards for the round using the selected category
-    use_effect(move || {
-        let player_count = players().len();
-        if cards().is_empty() && player_count > 0 {
-            if let Some(cat_index) = selected_category_index() {
-                let (new_cards, new_imposter, cat_name, cat_icon) = generate_cards_for_category(player_count, cat_index);
-                
-                // Store the words for this round (civilian word, imposter word)
-                if !new_cards.is_empty() {
-                    let civilian_word = new_cards.iter()
-                        .find(|c| c.card_type == CardType::Normal)
-                        .map(|c| c.word.clone())
-                        .unwrap_or_default();
-                    let imposter_word = new_cards.iter()
-                        .find(|c| c.card_type == CardType::Imposter)
-                        .map(|c| c.word.clone())
-                        .unwrap_or_default();
-                    current_round_words.set(Some((civilian_word, imposter_word)));
-                }
-                
-                cards.set(new_cards);
-                imposter_index.set(new_imposter);
-                current_category.set(Some((cat_name.clone(), cat_icon.clone())));
-            }
-        }
-    });
+    let GameState { players: player_list, cards: cards_list, starting_player_index, hide_imposter_identity, .. } = state();
 
-    let player_list = players();
-    let cards_list = cards();
-    
     // Calculate rotated player order
     let total_players = player_list.len();
-    let start_idx = starting_player_index() % total_players;
+    let start_idx = starting_player_index % total_players;
     let actual_player_index = (start_idx + current_player_index) % total_players;
     
     if current_player_index >= player_list.len() {
@@ -57,7 +21,7 @@ pub fn CardViewScreen(
                 button {
                     class: "proceed-btn",
                     onclick: move |_| {
-                        game_screen.set(GameScreen::Voting);
+                        dispatch(state, GameAction::ProceedToVoting);
                     },
                     "Proceed to Discussion"
                 }
@@ -80,14 +44,17 @@ pub fn CardViewScreen(
     
     // Determine what to show based on hard mode
     let is_imposter = current_card.card_type == CardType::Imposter;
-    let hard_mode_enabled = hide_imposter_identity();
+    let hard_mode_enabled = hide_imposter_identity;
     let should_hide_imposter = hard_mode_enabled && is_imposter;
+    // Mr. White has no word, so there's nothing for hard mode to hide
+    let is_mr_white = current_card.card_type == CardType::MrWhite;
     
     // Always show each player's assigned word; hard mode only hides identity text
     let displayed_word = current_card.word.clone();
 
     rsx! {
         div { class: "card-view-screen",
+            PhaseTimerBar { state }
             if !card_revealed() {
                 div { class: "player-ready-screen",
                     h2 { "Pass device to:" }
@@ -100,12 +67,7 @@ pub fn CardViewScreen(
                             button {
                                 class: "back-btn",
                                 onclick: move |_| {
-                                    if let Some((cat_name, cat_icon)) = current_category() {
-                                        game_screen.set(GameScreen::CategoryReveal {
-                                            category_name: cat_name,
-                                            category_icon: cat_icon,
-                                        });
-                                    }
+                                    dispatch(state, GameAction::BackToCategoryReveal);
                                 },
                                 "← Back"
                             }
@@ -124,37 +86,47 @@ pub fn CardViewScreen(
                 div { class: "card-revealed-screen",
                     h2 { "{current_player.name}'s Card" }
                     
-                    div { 
-                        class: if should_hide_imposter {
-                            "game-card normal-card"
-                        } else if is_imposter {
-                            "game-card imposter-card"
-                        } else {
-                            "game-card normal-card"
-                        },
-                        div { class: "card-word",
-                            "{displayed_word}"
+                    if is_mr_white {
+                        div { class: "game-card mr-white-card",
+                            div { class: "card-word", "???" }
+                            div { class: "card-type-hint", "🕴️ You are MR. WHITE!" }
                         }
-                        if !hard_mode_enabled {
-                            div { class: "card-type-hint",
-                                if should_hide_imposter {
-                                    "👥 You are a civilian"
-                                } else if is_imposter {
-                                    "🎭 You are the IMPOSTER!"
-                                } else {
-                                    "👥 You are a civilian"
+                        p { class: "card-instruction",
+                            "You have no word. Listen closely and bluff - if you're evicted, name the civilian word to steal the win."
+                        }
+                    } else {
+                        div { 
+                            class: if should_hide_imposter {
+                                "game-card normal-card"
+                            } else if is_imposter {
+                                "game-card imposter-card"
+                            } else {
+                                "game-card normal-card"
+                            },
+                            div { class: "card-word",
+                                "{displayed_word}"
+                            }
+                            if !hard_mode_enabled {
+                                div { class: "card-type-hint",
+                                    if should_hide_imposter {
+                                        "👥 You are a civilian"
+                                    } else if is_imposter {
+                                        "🎭 You are the IMPOSTER!"
+                                    } else {
+                                        "👥 You are a civilian"
+                                    }
                                 }
                             }
                         }
-                    }
                     
-                    p { class: "card-instruction",
-                        if should_hide_imposter {
-                            "Find the player with the different word!"
-                        } else if is_imposter {
-                            "Try to blend in! Don't let others know you have the odd word."
-                        } else {
-                            "Find the player with the different word!"
+                        p { class: "card-instruction",
+                            if should_hide_imposter {
+                                "Find the player with the different word!"
+                            } else if is_imposter {
+                                "Try to blend in! Don't let others know you have the odd word."
+                            } else {
+                                "Find the player with the different word!"
+                            }
                         }
                     }
                     
@@ -162,9 +134,7 @@ pub fn CardViewScreen(
                         class: "next-btn",
                         onclick: move |_| {
                             card_revealed.set(false);
-                            game_screen.set(GameScreen::CardView {
-                                current_player_index: current_player_index + 1
-                            });
+                            dispatch(state, GameAction::RevealCard);
                         },
                         "Next Player"
                     }
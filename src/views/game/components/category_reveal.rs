@@ -1,13 +1,19 @@
 use dioxus::prelude::*;
-use crate::views::game::types::*;
+use crate::views::game::{dispatch, types::*, GameAction};
 
 #[component]
 pub fn CategoryRevealScreen(
     category_name: String,
     category_icon: String,
-    mut game_screen: Signal<GameScreen>,
-    mut hide_imposter_identity: Signal<bool>,
+    state: Signal<GameState>,
 ) -> Element {
+    let hard_mode = state.read().hide_imposter_identity;
+    let fair_deal = state.read().fair_deal;
+    let max_streak = state.read().max_imposter_streak;
+    let last_chance = state.read().imposter_last_chance;
+    let ballot_mode = state.read().ballot_mode;
+    let tie_break = state.read().tie_break;
+
     rsx! {
         div { class: "category-reveal-screen",
             div { class: "category-card",
@@ -24,9 +30,9 @@ pub fn CategoryRevealScreen(
                         input {
                             r#type: "checkbox",
                             class: "toggle-checkbox",
-                            checked: hide_imposter_identity(),
+                            checked: hard_mode,
                             oninput: move |evt| {
-                                hide_imposter_identity.set(evt.value() == "true");
+                                dispatch(state, GameAction::SetHardMode(evt.value() == "true"));
                             }
                         }
                         span { class: "toggle-text",
@@ -34,7 +40,7 @@ pub fn CategoryRevealScreen(
                         }
                     }
                     p { class: "hard-mode-description",
-                        if hide_imposter_identity() {
+                        if hard_mode {
                             "The imposter won't know they're the imposter!"
                         } else {
                             "Enable to hide the imposter's identity from themselves"
@@ -42,11 +48,122 @@ pub fn CategoryRevealScreen(
                     }
                 }
                 
+                // Fair Deal Toggle
+                div { class: "hard-mode-toggle",
+                    label { class: "toggle-label",
+                        input {
+                            r#type: "checkbox",
+                            class: "toggle-checkbox",
+                            checked: fair_deal,
+                            oninput: move |evt| {
+                                dispatch(state, GameAction::SetFairDeal(evt.value() == "true"));
+                            }
+                        }
+                        span { class: "toggle-text",
+                            "⚖️ Fair Deal"
+                        }
+                    }
+                    p { class: "hard-mode-description",
+                        if fair_deal {
+                            "Players who've been the imposter least are more likely to get it"
+                        } else {
+                            "Enable to spread the imposter role evenly"
+                        }
+                    }
+                    if fair_deal {
+                        label { class: "streak-cap",
+                            "Max rounds in a row as imposter "
+                            select {
+                                value: "{max_streak}",
+                                onchange: move |evt| {
+                                    let cap = evt.value().parse().unwrap_or(0);
+                                    dispatch(state, GameAction::SetMaxImposterStreak(cap));
+                                },
+                                option { value: "0", "No limit" }
+                                for cap in 1..=3u32 {
+                                    option { value: "{cap}", "{cap}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Last Chance Toggle
+                div { class: "hard-mode-toggle",
+                    label { class: "toggle-label",
+                        input {
+                            r#type: "checkbox",
+                            class: "toggle-checkbox",
+                            checked: last_chance,
+                            oninput: move |evt| {
+                                dispatch(state, GameAction::SetLastChance(evt.value() == "true"));
+                            }
+                        }
+                        span { class: "toggle-text",
+                            "🎯 Last Chance"
+                        }
+                    }
+                    p { class: "hard-mode-description",
+                        if last_chance {
+                            "Evicted imposters get one guess at the civilian word for bonus points"
+                        } else {
+                            "Enable to let evicted imposters guess the civilian word"
+                        }
+                    }
+                }
+                
+                // Secret Ballot Toggle
+                div { class: "hard-mode-toggle",
+                    label { class: "toggle-label",
+                        input {
+                            r#type: "checkbox",
+                            class: "toggle-checkbox",
+                            checked: ballot_mode,
+                            oninput: move |evt| {
+                                dispatch(state, GameAction::SetBallotMode(evt.value() == "true"));
+                            }
+                        }
+                        span { class: "toggle-text",
+                            "🗳️ Secret Ballot"
+                        }
+                    }
+                    p { class: "hard-mode-description",
+                        if ballot_mode {
+                            "Everyone votes in private and the votes are counted"
+                        } else {
+                            "Enable to have each player vote in secret"
+                        }
+                    }
+                    if ballot_mode {
+                        label { class: "streak-cap",
+                            "On a tie "
+                            select {
+                                value: match tie_break {
+                                    TieBreak::Revote => "revote",
+                                    TieBreak::NoEviction => "none",
+                                    TieBreak::Random => "random",
+                                },
+                                onchange: move |evt| {
+                                    let tie_break = match evt.value().as_str() {
+                                        "none" => TieBreak::NoEviction,
+                                        "random" => TieBreak::Random,
+                                        _ => TieBreak::Revote,
+                                    };
+                                    dispatch(state, GameAction::SetTieBreak(tie_break));
+                                },
+                                option { value: "revote", "Revote between the tied players" }
+                                option { value: "none", "Nobody is evicted" }
+                                option { value: "random", "Pick one at random" }
+                            }
+                        }
+                    }
+                }
+
                 div { class: "action-buttons-row",
                     button {
                         class: "back-btn",
                         onclick: move |_| {
-                            game_screen.set(GameScreen::CategorySelection);
+                            dispatch(state, GameAction::BackToCategories);
                         },
                         "← Back to Categories"
                     }
@@ -54,7 +171,7 @@ pub fn CategoryRevealScreen(
                     button {
                         class: "continue-btn",
                         onclick: move |_| {
-                            game_screen.set(GameScreen::CardView { current_player_index: 0 });
+                            dispatch(state, GameAction::StartRound);
                         },
                         "▶️ Start Round"
                     }
@@ -1,49 +1,146 @@
 use dioxus::prelude::*;
-use crate::views::game::{types::*, utils::get_all_categories};
+use crate::views::game::{
+    dispatch, load_local_word_packs, parse_word_pack, refresh_server_word_packs, remove_local_word_pack,
+    save_local_word_pack, server_word_packs, types::*, utils::{get_all_word_packs, remaining_pair_count}, GameAction,
+};
 
+/// A room host only gets the packs the room's server knows about, since the server deals the words
 #[component]
-pub fn CategorySelectionScreen(
-    mut game_screen: Signal<GameScreen>,
-    mut selected_category_index: Signal<Option<usize>>,
-) -> Element {
-    // Use a signal to store categories so they live long enough
-    let categories = use_signal(|| get_all_categories());
-    let cats = categories.read();
-    
+pub fn CategorySelectionScreen(state: Signal<GameState>, #[props(default)] room_host: bool) -> Element {
+    // Use a signal to store packs so they live long enough
+    let mut packs = use_signal(get_all_word_packs);
+    let mut upload_error = use_signal(|| None::<String>);
+    let disabled_packs = state.read().disabled_packs.clone();
+    let used_pairs = state.read().used_pairs.clone();
+
+    // Server packs may still be on their way
+    use_future(move || async move {
+        refresh_server_word_packs().await;
+        packs.set(get_all_word_packs());
+    });
+
+    let local_pack_ids: Vec<String> = load_local_word_packs().into_iter().map(|p| p.id).collect();
+    // In a room, leave out packs uploaded in this browser that the server doesn't have too
+    let server_pack_ids: Vec<String> = server_word_packs().into_iter().map(|p| p.id).collect();
+    let shown_packs: Vec<WordPack> = packs
+        .read()
+        .iter()
+        .filter(|pack| !room_host || !local_pack_ids.contains(&pack.id) || server_pack_ids.contains(&pack.id))
+        .cloned()
+        .collect();
+
+    let categories: Vec<WordCategory> = shown_packs
+        .iter()
+        .filter(|pack| !disabled_packs.contains(&pack.id))
+        .flat_map(|pack| pack.categories.clone())
+        .collect();
+
     rsx! {
         div { class: "category-selection-screen",
             div { class: "selection-header",
                 h1 { "🎯 Choose Your Category" }
                 p { class: "selection-subtitle", "Pick a theme for this round" }
             }
-            
+
             div { class: "categories-grid",
-                for (index, category) in cats.iter().enumerate() {
+                for category in categories {
                     {
-                        let cat_name = category.name.clone();
-                        let cat_icon = category.icon.clone();
+                        let used = used_pairs.get(&category.id).map(Vec::as_slice).unwrap_or_default();
+                        let remaining = remaining_pair_count(&category, used);
+                        let category_id = category.id.clone();
                         rsx! {
-                            div { 
-                                key: "{index}",
+                            div {
+                                key: "{category.id}",
                                 class: "category-card-selectable",
                                 onclick: move |_| {
-                                    selected_category_index.set(Some(index));
-                                    game_screen.set(GameScreen::CategoryReveal { 
-                                        category_name: cat_name.clone(),
-                                        category_icon: cat_icon.clone() 
-                                    });
+                                    dispatch(state, GameAction::PickCategory { category_id: category_id.clone() });
                                 },
-                                div { class: "category-icon-large", "{cat_icon}" }
-                                h3 { class: "category-name-selectable", "{cat_name}" }
-                                p { class: "category-pairs-count", 
-                                    "{category.pairs.len()} word pairs" 
+                                div { class: "category-icon-large", "{category.icon}" }
+                                h3 { class: "category-name-selectable", "{category.name}" }
+                                p { class: "category-pairs-count",
+                                    if remaining == category.pairs.len() {
+                                        "{category.pairs.len()} word pairs"
+                                    } else {
+                                        "{remaining} of {category.pairs.len()} pairs left"
+                                    }
                                 }
                             }
                         }
                     }
                 }
             }
+
+            div { class: "word-packs-section",
+                h2 { "📦 Word Packs" }
+                for pack in shown_packs {
+                    {
+                        let enabled = !disabled_packs.contains(&pack.id);
+                        let is_local = local_pack_ids.contains(&pack.id);
+                        let toggle_id = pack.id.clone();
+                        let delete_id = pack.id.clone();
+                        rsx! {
+                            div { key: "{pack.id}", class: "word-pack-row",
+                                label {
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: enabled,
+                                        onchange: move |e| {
+                                            dispatch(state, GameAction::SetPackEnabled {
+                                                pack_id: toggle_id.clone(),
+                                                enabled: e.checked(),
+                                            });
+                                        }
+                                    }
+                                    span { class: "word-pack-name", "{pack.name}" }
+                                    span { class: "hint", " ({pack.categories.len()} categories)" }
+                                }
+                                if is_local {
+                                    button {
+                                        class: "word-pack-remove",
+                                        onclick: move |_| {
+                                            remove_local_word_pack(&delete_id);
+                                            packs.set(get_all_word_packs());
+                                        },
+                                        "🗑️"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if room_host {
+                    p { class: "hint", "Packs added in this browser stay here, so they can't be played in a room." }
+                } else {
+                    label { class: "word-pack-upload",
+                        "➕ Add a pack (.yaml or .json)"
+                        input {
+                            r#type: "file",
+                            accept: ".yaml,.yml,.json",
+                            onchange: move |evt| async move {
+                                for file in evt.files() {
+                                    let name = file.name();
+                                    let parsed = match file.read_string().await {
+                                        Ok(contents) => parse_word_pack(&name, &contents),
+                                        Err(e) => Err(format!("{}: {}", name, e)),
+                                    };
+                                    match parsed {
+                                        Ok(pack) => {
+                                            save_local_word_pack(pack);
+                                            upload_error.set(None);
+                                        }
+                                        Err(e) => upload_error.set(Some(e)),
+                                    }
+                                }
+                                packs.set(get_all_word_packs());
+                            }
+                        }
+                    }
+                }
+                if let Some(error) = upload_error() {
+                    p { class: "room-error", "⚠️ {error}" }
+                }
+            }
         }
     }
 }
-
@@ -1,25 +1,73 @@
 use dioxus::prelude::*;
-use crate::views::game::types::*;
+use crate::views::game::{dispatch, types::*, GameAction, VoteBreakdown};
 
 /// Screen showing elimination results
 #[component]
 pub fn EliminationScreen(
-    mut players: Signal<Vec<Player>>,
     eliminated_index: usize,
     was_imposter: bool,
-    mut game_screen: Signal<GameScreen>,
-    mut round_number: Signal<i32>,
-    mut cards: Signal<Vec<GameCard>>,
-    imposter_index: Signal<usize>,
+    was_mr_white: bool,
+    state: Signal<GameState>,
 ) -> Element {
-    let player_list = players();
+    let GameState {
+        players: player_list,
+        imposter_indices,
+        mr_white_indices,
+        scoring,
+        vote_tallies,
+        round_number,
+        ..
+    } = state();
+    // The secret vote that led here, if there was one
+    let tally = vote_tallies
+        .last()
+        .filter(|t| t.round_number == round_number)
+        .filter(|t| {
+            matches!(t.outcome, VoteOutcome::Evicted(i) | VoteOutcome::RandomEviction { evicted: i, .. } if i == eliminated_index)
+        })
+        .cloned();
     let eliminated_player = &player_list[eliminated_index];
     let active_count = player_list.iter().filter(|p| !p.is_eliminated).count();
+    // Imposters and Mr. Whites still hiding once this eviction goes through
+    let imposters_left = imposter_indices
+        .iter()
+        .chain(mr_white_indices.iter())
+        .filter(|&&i| i != eliminated_index && !player_list[i].is_eliminated)
+        .count();
     
     rsx! {
         div { class: "elimination-screen",
             // Show different emoji and message based on who was evicted
-            if was_imposter {
+            if was_mr_white {
+                h1 { class: "mr-white-caught", "🕴️ Mr. White Evicted!" }
+                div { class: "elimination-result mr-white-chance",
+                    p { class: "eliminated-player",
+                        "🕴️ {eliminated_player.name} was MR. WHITE!"
+                    }
+                    p { class: "result-message",
+                        "🤔 But they get one guess at the civilian word..."
+                    }
+                }
+            } else if was_imposter && imposters_left > 0 {
+                h1 { class: "imposter-found", "🎉 Imposter Evicted!" }
+                div { class: "elimination-result celebration",
+                    p { class: "eliminated-player success",
+                        "🎊 {eliminated_player.name} was an IMPOSTER!"
+                    }
+                    p { class: "result-message",
+                        if !mr_white_indices.is_empty() {
+                            "😈 But the odd ones out aren't all gone yet..."
+                        } else if imposters_left == 1 {
+                            "😈 But one more imposter is still among you..."
+                        } else {
+                            "😈 But {imposters_left} more imposters are still among you..."
+                        }
+                    }
+                    p { class: "players-remaining",
+                        "{active_count - 1} players remaining"
+                    }
+                }
+            } else if was_imposter {
                 h1 { class: "imposter-found", "🎉 Imposter Evicted!" }
                 div { class: "elimination-result celebration",
                     p { class: "eliminated-player success",
@@ -29,7 +77,11 @@ pub fn EliminationScreen(
                         "🏆 Civilians win this round!"
                     }
                     p { class: "players-remaining",
-                        "Remaining civilians receive +10 points"
+                        if scoring.whole_team_scores {
+                            "Every civilian receives +{scoring.civilian_win} points"
+                        } else {
+                            "Remaining civilians receive +{scoring.civilian_win} points"
+                        }
                     }
                 }
             } else {
@@ -39,7 +91,18 @@ pub fn EliminationScreen(
                         "💔 {eliminated_player.name} was a CIVILIAN"
                     }
                     p { class: "result-message",
-                        "😈 The imposter remains among you..."
+                        if !mr_white_indices.is_empty() {
+                            "😈 The odd ones out remain among you..."
+                        } else if imposter_indices.len() > 1 {
+                            "😈 The imposters remain among you..."
+                        } else {
+                            "😈 The imposter remains among you..."
+                        }
+                    }
+                    if scoring.civilian_eviction_penalty != 0 {
+                        p { class: "players-remaining",
+                            "Remaining civilians lose {scoring.civilian_eviction_penalty} points"
+                        }
                     }
                     p { class: "players-remaining",
                         "{active_count - 1} players remaining"
@@ -47,51 +110,15 @@ pub fn EliminationScreen(
                 }
             }
             
+            if let Some(tally) = tally {
+                VoteBreakdown { tally, names: player_list.iter().map(|p| p.name.clone()).collect() }
+            }
+
             div { class: "action-buttons",
                 button {
                     class: "continue-btn",
                     onclick: move |_| {
-                        let mut updated_players = players();
-                        // Eliminate the player
-                        updated_players[eliminated_index].is_eliminated = true;
-                        
-                        // Check if imposter was eliminated
-                        if was_imposter {
-                            // Imposter found - civilians win!
-                            for (i, player) in updated_players.iter_mut().enumerate() {
-                                // Award only active civilians; evicted players get nothing
-                                if i != imposter_index() && !player.is_eliminated {
-                                    player.score += 10;
-                                }
-                            }
-                            players.set(updated_players);
-                            game_screen.set(GameScreen::RoundEnd { 
-                                imposter_found: true,
-                                game_over: true 
-                            });
-                        } else {
-                            // Check if only 2 players remain
-                            let remaining_count = updated_players.iter()
-                                .filter(|p| !p.is_eliminated)
-                                .count();
-                            
-                            players.set(updated_players);
-                            
-                            if remaining_count <= 2 {
-                                // Imposter wins!
-                                let mut final_players = players();
-                                final_players[imposter_index()].score += 20;
-                                players.set(final_players);
-                                game_screen.set(GameScreen::RoundEnd { 
-                                    imposter_found: false,
-                                    game_over: true 
-                                });
-                            } else {
-                                // Continue to next voting round
-                                round_number.set(round_number() + 1);
-                                game_screen.set(GameScreen::Voting);
-                            }
-                        }
+                        dispatch(state, GameAction::ConfirmElimination);
                     },
                     "Continue"
                 }
@@ -0,0 +1,159 @@
+use dioxus::prelude::*;
+use crate::views::game::{
+    describe_guess, dispatch, export_history, played_rounds, types::*, GameAction, PlayedRound, VoteBreakdown,
+};
+
+/// Hand the browser a file to save
+pub fn download(file_name: &str, contents: &str) {
+    // A JSON string literal is a valid JavaScript string literal too
+    let literal = serde_json::to_string(contents).unwrap_or_default();
+    let _ = document::eval(&format!(
+        r#"
+        const link = document.createElement("a");
+        link.href = URL.createObjectURL(new Blob([{literal}], {{ type: "application/json" }}));
+        link.download = "{file_name}";
+        link.click();
+        URL.revokeObjectURL(link.href);
+        "#
+    ));
+}
+
+/// Walk back through every round played this session, one at a time
+#[component]
+pub fn HistoryScreen(state: Signal<GameState>) -> Element {
+    let rounds = played_rounds(&state.read().history);
+    // None follows the latest round
+    let mut selected = use_signal(|| None::<usize>);
+    let last = rounds.len().saturating_sub(1);
+    let index = selected().unwrap_or(last).min(last);
+    let count = rounds.len();
+
+    rsx! {
+        div { class: "score-screen history-screen",
+            h1 { "📜 Game History" }
+
+            if let Some(round) = rounds.get(index).cloned() {
+                div { class: "history-nav",
+                    button {
+                        class: "history-nav-btn",
+                        disabled: index == 0,
+                        onclick: move |_| selected.set(Some(index.saturating_sub(1))),
+                        "←"
+                    }
+                    span { class: "round-info", "Game {round.game} · Round {round.round} ({index + 1} of {count})" }
+                    button {
+                        class: "history-nav-btn",
+                        disabled: index >= last,
+                        onclick: move |_| selected.set(Some(index + 1)),
+                        "→"
+                    }
+                }
+                HistoryRound { round }
+            } else {
+                p { class: "instruction", "No rounds played yet." }
+            }
+
+            div { class: "action-buttons",
+                button {
+                    class: "next-round-btn",
+                    disabled: count == 0,
+                    onclick: move |_| {
+                        let current = state.peek();
+                        match export_history(&current) {
+                            Ok(json) => download(&format!("imposter-history-{}.json", current.session_id), &json),
+                            Err(e) => dioxus::logger::tracing::warn!("Could not export the history: {}", e),
+                        }
+                    },
+                    "⬇️ Export JSON"
+                }
+                button {
+                    class: "new-game-btn",
+                    onclick: move |_| {
+                        dispatch(state, GameAction::ViewScores);
+                    },
+                    "← Back to Scores"
+                }
+            }
+        }
+    }
+}
+
+/// Everything logged for one round, in the order it happened
+#[component]
+fn HistoryRound(round: PlayedRound) -> Element {
+    let name = |i: usize| round.players.get(i).cloned().unwrap_or_else(|| "?".to_string());
+    let names = |indices: &[usize]| indices.iter().map(|&i| name(i)).collect::<Vec<_>>().join(", ");
+
+    rsx! {
+        div { class: "history-round",
+            for event in round.events.iter().cloned() {
+                match event {
+                    GameEvent::RoundStarted { category_name, category_icon, civilian_word, imposter_word, imposters, mr_whites, .. } => rsx! {
+                        h2 { "{category_icon} {category_name}" }
+                        div { class: "word-display",
+                            div { class: "word-item civilian-word-display",
+                                span { class: "word-label", "👥 Civilian Word:" }
+                                span { class: "word-value", "{civilian_word}" }
+                            }
+                            if !imposter_word.is_empty() {
+                                div { class: "word-item imposter-word-display",
+                                    span { class: "word-label", "🎭 Imposter Word:" }
+                                    span { class: "word-value", "{imposter_word}" }
+                                }
+                            }
+                        }
+                        if !imposters.is_empty() {
+                            p { class: "imposter-name", "🎭 {names(&imposters)}" }
+                        }
+                        if !mr_whites.is_empty() {
+                            p { class: "imposter-name", "🕴️ {names(&mr_whites)}" }
+                        }
+                    },
+                    GameEvent::VoteCounted(tally) => rsx! {
+                        VoteBreakdown { tally, names: round.players.clone() }
+                    },
+                    GameEvent::Evicted { player_index, card_type } => {
+                        let role = match card_type {
+                            CardType::Imposter => "an imposter!",
+                            CardType::MrWhite => "Mr. White!",
+                            CardType::Normal => "a civilian.",
+                        };
+                        rsx! {
+                            p { class: "history-event", "🚪 {name(player_index)} was evicted - {role}" }
+                        }
+                    }
+                    GameEvent::WordGuessed(guess) => rsx! {
+                        p { class: "history-event", "🎯 {describe_guess(&name(guess.player_index), &guess)}" }
+                    },
+                    GameEvent::RoundEnded { imposter_found, stolen_by, score_changes, scores } => {
+                        let result = match (imposter_found, stolen_by) {
+                            (true, _) => "✅ Civilians won".to_string(),
+                            (false, Some(thief)) => format!("🕴️ {} stole the win", name(thief)),
+                            (false, None) => "😈 The odd ones out won".to_string(),
+                        };
+                        rsx! {
+                            p { class: "vote-outcome", "{result}" }
+                            div { class: "history-scores",
+                                for (i, (change, total)) in score_changes.iter().zip(scores.iter()).enumerate() {
+                                    span { "{name(i)}" }
+                                    span { class: "history-change", if *change >= 0 { "+{change}" } else { "{change}" } }
+                                    span { "{total}" }
+                                }
+                            }
+                        }
+                    }
+                    GameEvent::RoundAbandoned => rsx! {
+                        p { class: "history-event", "🔄 Restarted before anyone won" }
+                    },
+                    GameEvent::PlayerJoined { name: joined, score, .. } => rsx! {
+                        p { class: "history-event", "👋 {joined} joined on {score} points" }
+                    },
+                    GameEvent::PlayerLeft { player_index } => rsx! {
+                        p { class: "history-event", "🚶 {name(player_index)} left the game" }
+                    },
+                    GameEvent::GameStarted { .. } => rsx! {},
+                }
+            }
+        }
+    }
+}
@@ -3,16 +3,32 @@ pub mod category_selection;
 pub mod category_reveal;
 pub mod card_view;
 pub mod voting;
+pub mod ballot;
+pub mod timer;
 pub mod elimination;
+pub mod word_guess;
 pub mod round_end;
 pub mod score;
+pub mod players;
+pub mod history;
+pub mod stats;
+pub mod saved_games;
+pub mod room;
 
 pub use setup::SetupScreen;
 pub use category_selection::CategorySelectionScreen;
 pub use category_reveal::CategoryRevealScreen;
 pub use card_view::CardViewScreen;
 pub use voting::VotingScreen;
+pub use ballot::{BallotScreen, VoteBreakdown};
+pub use timer::PhaseTimerBar;
 pub use elimination::EliminationScreen;
-pub use round_end::RoundEndScreen;
+pub use word_guess::{ImposterGuessScreen, MrWhiteGuessScreen};
+pub use round_end::{describe_guess, RoundEndScreen};
 pub use score::GameScoreScreen;
+pub use players::ManagePlayers;
+pub use history::HistoryScreen;
+pub use stats::Stats;
+pub use saved_games::SavedGames;
+pub use room::{RoomCardsDealtScreen, RoomHost, RoomJoin};
 
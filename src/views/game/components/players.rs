@@ -0,0 +1,90 @@
+use dioxus::prelude::*;
+use crate::views::game::{
+    dispatch, load_player_registry, starting_score, types::*, GameAction, MAX_PLAYERS, MIN_PLAYERS,
+};
+
+/// Add a latecomer or let someone go between rounds, without touching anyone's score
+#[component]
+pub fn ManagePlayers(state: Signal<GameState>) -> Element {
+    // Latecomers who have played before keep adding to their stats
+    let registry = use_hook(load_player_registry);
+    let mut new_name = use_signal(String::new);
+    let mut new_score = use_signal(StartingScore::default);
+    // Player waiting for a second tap to confirm they've left
+    let mut leaving = use_signal(|| None::<usize>);
+    let players = state.read().players.clone();
+    let average = starting_score(&state.read(), StartingScore::Average);
+    let can_remove = players.len() > MIN_PLAYERS;
+    let typed = new_name().trim().to_lowercase();
+    let taken = players.iter().any(|p| p.name.trim().to_lowercase() == typed);
+    let can_add = players.len() < MAX_PLAYERS && !typed.is_empty() && !taken;
+
+    rsx! {
+        details { class: "scoring-section manage-players",
+            summary { "👥 Players: add or remove" }
+            p { class: "hint", "Everyone keeps their score. Changes take effect from the next round." }
+            div { class: "manage-players-list",
+                for (i, player) in players.into_iter().enumerate() {
+                    div {
+                        key: "{player.name}",
+                        class: "manage-player",
+                        span { class: "manage-player-name", "{player.name}" }
+                        span { class: "hint", "{player.score} pts" }
+                        if leaving() == Some(i) {
+                            button {
+                                class: "confirm-yes-btn",
+                                onclick: move |_| {
+                                    leaving.set(None);
+                                    dispatch(state, GameAction::RemovePlayer { player_index: i });
+                                },
+                                "Remove"
+                            }
+                            button {
+                                class: "confirm-no-btn",
+                                onclick: move |_| leaving.set(None),
+                                "Keep"
+                            }
+                        } else {
+                            button {
+                                class: "roster-remove",
+                                title: "They've left",
+                                disabled: !can_remove,
+                                onclick: move |_| leaving.set(Some(i)),
+                                "✕"
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "roster-save",
+                input {
+                    r#type: "text",
+                    placeholder: "Latecomer's name",
+                    value: "{new_name}",
+                    oninput: move |e| new_name.set(e.value()),
+                }
+                select {
+                    onchange: move |e| {
+                        new_score.set(if e.value() == "average" { StartingScore::Average } else { StartingScore::Zero });
+                    },
+                    option { value: "zero", selected: new_score() == StartingScore::Zero, "Start on 0" }
+                    option { value: "average", selected: new_score() == StartingScore::Average, "Start on the average ({average})" }
+                }
+                button {
+                    class: "room-join-btn",
+                    disabled: !can_add,
+                    onclick: move |_| {
+                        let name = new_name();
+                        let id = registry.find_by_name(&name).map(|r| r.id.clone());
+                        dispatch(state, GameAction::AddPlayer { name, id, score: new_score() });
+                        new_name.set(String::new());
+                    },
+                    "➕ Add"
+                }
+            }
+            if taken {
+                p { class: "hint", "⚠️ Someone at the table already has that name" }
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use dioxus::fullstack::{use_websocket, WebSocketOptions};
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+use serde_json::Value;
+use crate::views::game::{
+    dispatch, room::*, save_player_stats, types::*, vote_candidates, GameAction, GameScreens, PhaseTimerBar,
+};
+
+const _GAME_CSS: Asset = asset!("/assets/styling/game.css");
+
+/// Keep a local copy of the room's state up to date from the server's messages
+fn apply_server_message(
+    message: ServerMessage,
+    snapshot: &mut Value,
+    mut state: Signal<GameState>,
+    mut rejected: Signal<Option<String>>,
+) {
+    match message {
+        ServerMessage::Patch(patch) => merge_apply(snapshot, &patch),
+        ServerMessage::Snapshot(view) => *snapshot = view,
+        ServerMessage::Rejected(reason) => {
+            rejected.set(Some(reason));
+            return;
+        }
+    }
+    if let Ok(next) = serde_json::from_value(snapshot.clone()) {
+        state.set(next);
+        rejected.set(None);
+    }
+}
+
+/// Host device for a multi-phone game: creates a room and drives the shared screens
+#[component]
+pub fn RoomHost() -> Element {
+    let room = use_resource(|| async move { create_room().await });
+
+    rsx! {
+        document::Stylesheet { href: _GAME_CSS }
+        div { class: "game-container",
+            match &*room.read() {
+                Some(Ok(ticket)) => rsx! {
+                    RoomHostSession { code: ticket.code.clone(), host_token: ticket.host_token.clone() }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "loading-screen",
+                        p { "Could not create a room: {e}" }
+                    }
+                },
+                None => rsx! {
+                    div { class: "loading-screen",
+                        p { "Creating room..." }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn RoomHostSession(code: String, host_token: String) -> Element {
+    let state = use_signal(|| GameState::new(code.clone()));
+    let rejected = use_signal(|| None::<String>);
+
+    let socket_code = code.clone();
+    let mut socket = use_websocket(move || {
+        room_socket(
+            socket_code.clone(),
+            None,
+            host_token.clone(),
+            WebSocketOptions::new().with_automatic_reconnect(),
+        )
+    });
+
+    use_future(move || async move {
+        let mut snapshot = Value::Null;
+        while let Ok(message) = socket.recv().await {
+            apply_server_message(message, &mut snapshot, state, rejected);
+        }
+    });
+
+    // The host's browser keeps the lifetime stats for everyone in the room
+    use_effect(move || save_player_stats(&state.read()));
+
+    // Screens call `dispatch`, which forwards to this coroutine while hosting a room
+    use_coroutine(move |mut actions: UnboundedReceiver<GameAction>| async move {
+        while let Some(action) = actions.next().await {
+            let _ = socket.send(ClientMessage::Action(action)).await;
+        }
+    });
+
+    rsx! {
+        div { class: "room-banner",
+            span { "📡 Room code: " }
+            strong { class: "room-code", "{code}" }
+            span { class: "hint", " Players join at /join/{code}" }
+        }
+        if let Some(reason) = rejected() {
+            p { class: "room-error", "⚠️ {reason}" }
+        }
+        GameScreens { state, room_host: true }
+    }
+}
+
+/// Shown on the host while every player looks at the card on their own phone
+#[component]
+pub fn RoomCardsDealtScreen(state: Signal<GameState>) -> Element {
+    rsx! {
+        div { class: "transition-screen",
+            h2 { "📱 Cards are dealt!" }
+            p { class: "instruction", "Everyone check the card on your own phone." }
+            button {
+                class: "proceed-btn",
+                onclick: move |_| {
+                    dispatch(state, GameAction::ProceedToVoting);
+                },
+                "Proceed to Discussion"
+            }
+        }
+    }
+}
+
+/// Player device for a multi-phone game: pick your name, then follow along
+#[component]
+pub fn RoomJoin(code: String) -> Element {
+    let mut name_input = use_signal(String::new);
+    // The seat this phone sits in, and the secret that proves it
+    let mut seat = use_signal(|| None::<(String, String)>);
+    let mut join_error = use_signal(|| None::<String>);
+
+    let join = {
+        let code = code.clone();
+        move |_| {
+            let name = name_input().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let code = code.clone();
+            spawn(async move {
+                // A phone that joined before sits back down in the same seat
+                let secret = match load_seat_secret(&code, &name) {
+                    Some(secret) => Ok(secret),
+                    None => join_room(code.clone(), name.clone()).await,
+                };
+                match secret {
+                    Ok(secret) => {
+                        store_seat_secret(&code, &name, &secret);
+                        join_error.set(None);
+                        seat.set(Some((name, secret)));
+                    }
+                    Err(e) => join_error.set(Some(e.to_string())),
+                }
+            });
+        }
+    };
+
+    rsx! {
+        document::Stylesheet { href: _GAME_CSS }
+        div { class: "game-container",
+            if let Some((name, secret)) = seat() {
+                RoomPlayerSession { code: code.clone(), name, secret }
+            } else {
+                div { class: "setup-screen",
+                    div { class: "setup-header",
+                        h1 { "📡 Join Room {code}" }
+                        p { class: "subtitle", "Use the name the host typed in for you" }
+                    }
+                    div { class: "player-input",
+                        input {
+                            r#type: "text",
+                            placeholder: "Your name...",
+                            value: "{name_input}",
+                            oninput: move |e| name_input.set(e.value()),
+                        }
+                    }
+                    button {
+                        class: "start-game-btn",
+                        onclick: join,
+                        "🚪 Join"
+                    }
+                    if let Some(error) = join_error() {
+                        p { class: "room-error", "⚠️ {error}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RoomPlayerSession(code: String, name: String, secret: String) -> Element {
+    let state = use_signal(|| GameState::new(code.clone()));
+    let rejected = use_signal(|| None::<String>);
+    let mut card_revealed = use_signal(|| false);
+
+    let (socket_code, seat) = (code.clone(), name.clone());
+    let mut socket = use_websocket(move || {
+        room_socket(
+            socket_code.clone(),
+            Some(seat.clone()),
+            secret.clone(),
+            WebSocketOptions::new().with_automatic_reconnect(),
+        )
+    });
+
+    use_future(move || async move {
+        let mut snapshot = Value::Null;
+        while let Ok(message) = socket.recv().await {
+            apply_server_message(message, &mut snapshot, state, rejected);
+        }
+    });
+
+    let view = state();
+    let my_index = seat_index(&view, &name);
+    let my_card = my_index.and_then(|i| view.cards.get(i)).filter(|c| c.card_type == CardType::MrWhite || !c.word.is_empty()).cloned();
+    let is_eliminated = my_index.and_then(|i| view.players.get(i)).is_some_and(|p| p.is_eliminated);
+    // Secret ballot: who this phone can vote for, or who it already voted for
+    let my_ballot = my_index.and_then(|i| view.ballots.get(&i).copied());
+    let ballot_choices: Vec<usize> = match (&view.game_screen, my_index) {
+        (GameScreen::Ballot, Some(me)) if !is_eliminated && my_ballot.is_none() => {
+            vote_candidates(&view).into_iter().filter(|&i| i != me).collect()
+        }
+        _ => Vec::new(),
+    };
+    let voted_for = my_ballot.flatten().and_then(|i| view.players.get(i)).map(|p| p.name.clone());
+
+    let status = match &view.game_screen {
+        GameScreen::Setup => "Waiting for the host to set up the game...".to_string(),
+        GameScreen::CategorySelection => "The host is choosing a category...".to_string(),
+        GameScreen::CategoryReveal { category_name, category_icon } => {
+            format!("{} {} - get ready!", category_icon, category_name)
+        }
+        GameScreen::CardView { .. } => "Look at your card, then wait for the discussion.".to_string(),
+        GameScreen::Voting => "Discuss and vote on the host's screen.".to_string(),
+        GameScreen::Ballot if !view.revote_candidates.is_empty() => "⚖️ It's a tie! Vote again.".to_string(),
+        GameScreen::Ballot => "🗳️ Secret vote - who should be evicted?".to_string(),
+        GameScreen::Elimination { eliminated_index, was_imposter, was_mr_white } => {
+            let evicted = view.players.get(*eliminated_index).map(|p| p.name.clone()).unwrap_or_default();
+            if *was_mr_white {
+                format!("{} was MR. WHITE!", evicted)
+            } else if *was_imposter {
+                format!("{} was an IMPOSTER!", evicted)
+            } else {
+                format!("{} was a civilian.", evicted)
+            }
+        }
+        GameScreen::MrWhiteGuess { .. } => "🕴️ Mr. White gets one guess at the civilian word...".to_string(),
+        GameScreen::ImposterGuess { .. } => "🎯 The imposter gets one last guess at the civilian word...".to_string(),
+        GameScreen::RoundEnd { imposter_found: true, .. } => "✅ Civilians win!".to_string(),
+        GameScreen::RoundEnd { stolen_by: Some(_), .. } => "🕴️ Mr. White steals the win!".to_string(),
+        GameScreen::RoundEnd { .. } if view.imposter_indices.is_empty() => "🕴️ Mr. White wins!".to_string(),
+        GameScreen::RoundEnd { imposter_found: false, .. } if view.imposter_indices.len() > 1 => {
+            "😈 Imposters win!".to_string()
+        }
+        GameScreen::RoundEnd { imposter_found: false, .. } => "😈 Imposter wins!".to_string(),
+        GameScreen::GameScore => "🏆 Scores are up on the host's screen.".to_string(),
+        GameScreen::History => "📜 The host is looking back over the game.".to_string(),
+    };
+
+    rsx! {
+        div { class: "card-view-screen",
+            div { class: "room-banner",
+                span { "📡 Room " }
+                strong { class: "room-code", "{code}" }
+                span { " · {name}" }
+            }
+
+            if view.game_screen != GameScreen::Setup && my_index.is_none() {
+                p { class: "room-error", "⚠️ No player called \"{name}\" in this game. Ask the host to check the names." }
+            }
+
+            div { class: "player-ready-screen",
+                h2 { "{status}" }
+                PhaseTimerBar { state, readonly: true }
+
+                if let (GameScreen::Setup, Some(seed)) = (&view.game_screen, view.last_game_seed) {
+                    p { class: "seed-info", "🎲 The last game's seed was {seed}" }
+                }
+
+                if let Some(card) = my_card {
+                    if card_revealed() {
+                        div {
+                            class: match card.card_type {
+                                CardType::Imposter => "game-card imposter-card",
+                                CardType::MrWhite => "game-card mr-white-card",
+                                CardType::Normal => "game-card normal-card",
+                            },
+                            if card.card_type == CardType::MrWhite {
+                                div { class: "card-word", "???" }
+                                div { class: "card-type-hint", "🕴️ You are MR. WHITE!" }
+                            } else {
+                                div { class: "card-word", "{card.word}" }
+                            }
+                            if !view.hide_imposter_identity && card.card_type != CardType::MrWhite {
+                                div { class: "card-type-hint",
+                                    if card.card_type == CardType::Imposter {
+                                        "🎭 You are the IMPOSTER!"
+                                    } else {
+                                        "👥 You are a civilian"
+                                    }
+                                }
+                            }
+                        }
+                        button {
+                            class: "next-btn",
+                            onclick: move |_| card_revealed.set(false),
+                            "Hide My Card"
+                        }
+                    } else {
+                        button {
+                            class: "reveal-btn",
+                            onclick: move |_| card_revealed.set(true),
+                            "Reveal My Card"
+                        }
+                    }
+                }
+
+                if is_eliminated {
+                    p { class: "instruction", "You've been evicted this round." }
+                }
+
+                if let Some(name) = voted_for {
+                    p { class: "instruction", "✅ You voted for {name}. Waiting for the others..." }
+                }
+
+                div { class: "players-voting-list",
+                    for target in ballot_choices {
+                        div { class: "player-voting-card",
+                            div { class: "player-info",
+                                h3 { "{view.players[target].name}" }
+                            }
+                            button {
+                                class: "evict-btn",
+                                onclick: move |_| {
+                                    spawn(async move {
+                                        let _ = socket.send(ClientMessage::Vote { target }).await;
+                                    });
+                                },
+                                "Vote"
+                            }
+                        }
+                    }
+                }
+
+                if let Some((civilian_word, imposter_word)) = view.current_round_words.clone() {
+                    div { class: "words-reveal",
+                        h3 { "📝 Words This Round:" }
+                        div { class: "word-display",
+                            div { class: "word-item civilian-word-display",
+                                span { class: "word-label", "👥 Civilian Word:" }
+                                span { class: "word-value", "{civilian_word}" }
+                            }
+                            if !imposter_word.is_empty() {
+                                div { class: "word-item imposter-word-display",
+                                    span { class: "word-label", "🎭 Imposter Word:" }
+                                    span { class: "word-value", "{imposter_word}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,22 +1,60 @@
 use dioxus::prelude::*;
-use crate::views::game::types::*;
-use crate::views::game::utils::get_random_starting_index;
+use crate::views::game::{dispatch, match_winners, start_new_game, types::*, GameAction, ManagePlayers, VoteBreakdown};
+
+/// One line saying what a player guessed and how close it was
+pub fn describe_guess(name: &str, guess: &WordGuess) -> String {
+    let verdict = match guess.result {
+        GuessMatch::Exact => "✅ spot on",
+        GuessMatch::Close => "🤏 close enough",
+        GuessMatch::Miss => "❌ missed",
+    };
+    format!("{} guessed \"{}\" - {}", name, guess.guess, verdict)
+}
 
 /// Screen showing round results
 #[component]
 pub fn RoundEndScreen(
-    mut players: Signal<Vec<Player>>,
     imposter_found: bool,
     game_over: bool,
-    mut game_screen: Signal<GameScreen>,
-    mut round_number: Signal<i32>,
-    mut cards: Signal<Vec<GameCard>>,
-    mut imposter_index: Signal<usize>,
-    current_round_words: Signal<Option<(String, String)>>,
-    mut starting_player_index: Signal<usize>,
+    stolen_by: Option<usize>,
+    state: Signal<GameState>,
 ) -> Element {
-    let player_list = players();
-    let imposter_name = &player_list[imposter_index()].name;
+    let GameState {
+        players: player_list,
+        imposter_indices,
+        mr_white_indices,
+        current_round_words,
+        guesses,
+        scoring,
+        vote_tallies,
+        ..
+    } = state();
+    let names = |indices: &std::collections::BTreeSet<usize>| {
+        indices
+            .iter()
+            .filter_map(|&i| player_list.get(i))
+            .map(|p| p.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let imposter_names = names(&imposter_indices);
+    let mr_white_names = names(&mr_white_indices);
+    let guess_lines: Vec<String> = guesses
+        .iter()
+        .map(|g| describe_guess(player_list.get(g.player_index).map(|p| p.name.as_str()).unwrap_or("?"), g))
+        .collect();
+    let thief_name = stolen_by.and_then(|i| player_list.get(i)).map(|p| p.name.clone());
+    let several = imposter_indices.len() > 1;
+    let has_imposters = !imposter_indices.is_empty();
+    let has_mr_white = !mr_white_indices.is_empty();
+    // Someone reached the target score: the match is over
+    let current = state();
+    let champions = match_winners(&current)
+        .into_iter()
+        .map(|i| current.players[i].name.clone())
+        .collect::<Vec<_>>()
+        .join(" & ");
+    let target = current.scoring.target_score.unwrap_or_default();
     let mut show_confirmation = use_signal(|| false);
 
     rsx! {
@@ -26,22 +64,13 @@ pub fn RoundEndScreen(
                 div { class: "confirmation-overlay",
                     div { class: "confirmation-dialog",
                         h2 { "⚠️ Start New Game?" }
-                        p { "All player scores will be reset. Are you sure?" }
+                        p { "Scores start again from zero. This game stays in 💾 Saved games, so you can come back to it." }
                         div { class: "confirmation-buttons",
                             button {
                                 class: "confirm-yes-btn",
                                 onclick: move |_| {
-                                    // Clear all game state for a fresh start
-                                    cards.set(Vec::new());
-                                    imposter_index.set(0);
-                                    round_number.set(1);
-                                    
-                                    // Randomize starting player for new game
-                                    let player_count = players().len();
-                                    starting_player_index.set(get_random_starting_index(player_count));
-                                    
                                     show_confirmation.set(false);
-                                    game_screen.set(GameScreen::Setup);
+                                    start_new_game(state);
                                 },
                                 "Yes, Start New Game"
                             }
@@ -60,18 +89,33 @@ pub fn RoundEndScreen(
             h1 {
                 if imposter_found {
                     "✅ Civilians Win!"
+                } else if thief_name.is_some() {
+                    "🕴️ Mr. White Steals the Win!"
+                } else if !has_imposters {
+                    "🕴️ Mr. White Wins!"
+                } else if several {
+                    "😈 Imposters Win!"
                 } else {
                     "😈 Imposter Wins!"
                 }
             }
             
             div { class: "round-result",
-                p { class: "imposter-reveal",
-                    "The imposter was: {imposter_name}"
+                if has_imposters {
+                    p { class: "imposter-reveal",
+                        if several {
+                            "The imposters were: {imposter_names}"
+                        } else {
+                            "The imposter was: {imposter_names}"
+                        }
+                    }
+                }
+                if has_mr_white {
+                    p { class: "imposter-reveal", "Mr. White was: {mr_white_names}" }
                 }
                 
                 // Display the words that were used this round
-                if let Some((civilian_word, imposter_word)) = current_round_words() {
+                if let Some((civilian_word, imposter_word)) = current_round_words {
                     div { class: "words-reveal",
                         h3 { "📝 Words This Round:" }
                         div { class: "word-display",
@@ -79,60 +123,104 @@ pub fn RoundEndScreen(
                                 span { class: "word-label", "👥 Civilian Word:" }
                                 span { class: "word-value", "{civilian_word}" }
                             }
-                            div { class: "word-item imposter-word-display",
-                                span { class: "word-label", "🎭 Imposter Word:" }
-                                span { class: "word-value", "{imposter_word}" }
+                            if !imposter_word.is_empty() {
+                                div { class: "word-item imposter-word-display",
+                                    span { class: "word-label", "🎭 Imposter Word:" }
+                                    span { class: "word-value", "{imposter_word}" }
+                                }
                             }
                         }
                     }
                 }
                 
+                if !vote_tallies.is_empty() {
+                    div { class: "guess-summary",
+                        h3 { "🗳️ Votes" }
+                        for tally in vote_tallies {
+                            VoteBreakdown { tally, names: player_list.iter().map(|p| p.name.clone()).collect() }
+                        }
+                    }
+                }
+
+                if !guess_lines.is_empty() {
+                    div { class: "guess-summary",
+                        h3 { "🎯 Guesses" }
+                        for line in guess_lines {
+                            p { "{line}" }
+                        }
+                    }
+                }
+
                 if imposter_found {
                     p { class: "result-message",
-                        "🎉 Remaining civilians get 10 points!"
+                        if scoring.whole_team_scores {
+                            "🎉 Every civilian gets {scoring.civilian_win} points!"
+                        } else {
+                            "🎉 Remaining civilians get {scoring.civilian_win} points!"
+                        }
+                    }
+                } else if let Some(thief) = thief_name {
+                    p { class: "result-message",
+                        "🕴️ {thief} named the civilian word and gets {scoring.mr_white_guess} points!"
+                    }
+                } else if has_mr_white {
+                    p { class: "result-message",
+                        "😈 The odd ones out still standing get {scoring.imposter_survival} points!"
+                    }
+                } else if several {
+                    p { class: "result-message",
+                        "😈 Imposters still standing get {scoring.imposter_survival} points!"
                     }
                 } else {
                     p { class: "result-message",
-                        "😈 The imposter gets 20 points!"
+                        "😈 The imposter gets {scoring.imposter_survival} points!"
                     }
                 }
 
                 div { class: "imposter-summary",
                     h3 { "🕵️ Imposter Reveal" }
-                    p { class: "imposter-name",
-                        "Imposter: {imposter_name}"
+                    if has_imposters {
+                        p { class: "imposter-name",
+                            if several {
+                                "Imposters: {imposter_names}"
+                            } else {
+                                "Imposter: {imposter_names}"
+                            }
+                        }
+                    }
+                    if has_mr_white {
+                        p { class: "imposter-name", "Mr. White: {mr_white_names}" }
                     }
                 }
             }
             
+            if !champions.is_empty() {
+                div { class: "match-winner",
+                    h2 { "🏆 {champions} won the match!" }
+                    p { "First to {target} points" }
+                    p { class: "seed-info", "🎲 Seed {current.game_seed}" }
+                }
+            } else {
+                // Latecomers and early leavers only matter while there's another round to play
+                ManagePlayers { state }
+            }
+
             div { class: "action-buttons",
-                button {
-                    class: "next-round-btn",
-                    onclick: move |_| {
-                        // Reset all player states for new round
-                        let mut updated_players = players();
-                        for player in updated_players.iter_mut() {
-                            player.is_eliminated = false; // Reset eliminations for new round
-                        }
-                        players.set(updated_players);
-                        cards.set(Vec::new());
-                        round_number.set(round_number() + 1);
-                        
-                        // Rotate starting player for next round
-                        let player_count = players().len();
-                        if player_count > 0 {
-                            starting_player_index.set((starting_player_index() + 1) % player_count);
-                        }
-                        
-                        game_screen.set(GameScreen::CategorySelection);
-                    },
-                    "▶️ Next Round"
+                // Nothing left to play once someone reaches the target score
+                if champions.is_empty() {
+                    button {
+                        class: "next-round-btn",
+                        onclick: move |_| {
+                            dispatch(state, GameAction::NextRound);
+                        },
+                        "▶️ Next Round"
+                    }
                 }
                 
                 button {
                     class: "view-scores-btn",
                     onclick: move |_| {
-                        game_screen.set(GameScreen::GameScore);
+                        dispatch(state, GameAction::ViewScores);
                     },
                     "🏆 View Scores"
                 }
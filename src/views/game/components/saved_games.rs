@@ -0,0 +1,344 @@
+use dioxus::prelude::*;
+use qrcode::{render::svg, EcLevel, QrCode};
+use super::history::download;
+use crate::views::game::{
+    copy_recorded_progress, delete_game_state, duplicate_game, export_code, export_game,
+    generate_session_id, import_game, load_game_state_from_disk, load_saved_games, load_session_id, now_ms,
+    played_ago, round_in_play, save_session_id, store_saved_game, summarize, types::*,
+};
+
+const _GAME_CSS: Asset = asset!("/assets/styling/game.css");
+
+/// The payload as a QR code, or `None` if it's too long to fit in one
+fn qr_svg(payload: &str) -> Option<String> {
+    let code = QrCode::with_error_correction_level(payload.as_bytes(), EcLevel::L).ok()?;
+    Some(code.render::<svg::Color>().min_dimensions(240, 240).build())
+}
+
+/// Put some text on the clipboard
+fn copy_to_clipboard(text: &str) {
+    // A JSON string literal is a valid JavaScript string literal too
+    let literal = serde_json::to_string(text).unwrap_or_default();
+    let _ = document::eval(&format!("navigator.clipboard.writeText({literal});"));
+}
+
+/// A game ID brings back the server's copy of that game; anything else is read as an export
+async fn fetch_import(payload: &str) -> Result<GameState, String> {
+    let payload = payload.trim();
+    if uuid::Uuid::parse_str(payload).is_err() {
+        return import_game(payload).map_err(|e| e.to_string());
+    }
+    match load_game_state_from_disk(payload).await {
+        Ok(Some(game)) => Ok(GameState { session_id: payload.to_string(), ..game }),
+        Ok(None) => Err("No game with that ID was found on the server".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Every game this browser created or imported, to pick one up again or tidy them away
+#[component]
+pub fn SavedGames() -> Element {
+    // Bumped after a change so the list is read again
+    let mut revision = use_signal(|| 0u32);
+    let games = use_resource(move || async move {
+        revision();
+        load_saved_games().await
+    });
+    let current = use_hook(load_session_id);
+    // Game being renamed, and the name typed so far
+    let mut renaming = use_signal(|| None::<String>);
+    let mut new_name = use_signal(String::new);
+    // Game waiting for a second tap to confirm it should go
+    let deleting = use_signal(|| None::<String>);
+    // Game whose export is open
+    let mut exporting = use_signal(|| None::<String>);
+    // Code pasted from another device, and why it couldn't be imported
+    let mut import_code = use_signal(String::new);
+    let mut import_error = use_signal(|| None::<String>);
+    let now = now_ms();
+
+    let resume = move |session_id: String| {
+        save_session_id(&session_id);
+        navigator().push("/");
+    };
+    // The imported game replaces any copy of the same session here, then carries on
+    let import = move |payload: String| async move {
+        match fetch_import(&payload).await {
+            Ok(game) => {
+                store_saved_game(&game);
+                resume(game.session_id);
+            }
+            Err(e) => import_error.set(Some(e)),
+        }
+    };
+
+    rsx! {
+        document::Stylesheet { href: _GAME_CSS }
+        div { class: "game-container",
+            div { class: "score-screen saved-games-screen",
+                h1 { "💾 Saved Games" }
+
+                match games() {
+                    None => rsx! {
+                        p { class: "instruction", "Looking for saved games..." }
+                    },
+                    Some(list) if list.is_empty() => rsx! {
+                        p { class: "instruction", "No saved games yet. Every game you play is kept here." }
+                    },
+                    Some(list) => rsx! {
+                        div { class: "scoreboard",
+                            for (session_id, restored) in list {
+                                div {
+                                    key: "{session_id}",
+                                    class: "score-card saved-game-card",
+                                    match restored {
+                                        Ok(game) => {
+                                            let summary = summarize(&game);
+                                            let playing = current.as_deref() == Some(session_id.as_str());
+                                            let standings = summary
+                                                .standings
+                                                .iter()
+                                                .map(|(name, score)| format!("{} {}", name, score))
+                                                .collect::<Vec<_>>()
+                                                .join(" · ");
+                                            rsx! {
+                                                div { class: "player-score-info",
+                                                    if renaming().as_deref() == Some(session_id.as_str()) {
+                                                        div { class: "roster-save",
+                                                            input {
+                                                                r#type: "text",
+                                                                placeholder: "{summary.name}",
+                                                                value: "{new_name}",
+                                                                oninput: move |e| new_name.set(e.value()),
+                                                            }
+                                                            button {
+                                                                class: "room-join-btn",
+                                                                onclick: {
+                                                                    let game = game.clone();
+                                                                    move |_| {
+                                                                        store_saved_game(&GameState { save_name: new_name().trim().to_string(), ..game.clone() });
+                                                                        renaming.set(None);
+                                                                        revision += 1;
+                                                                    }
+                                                                },
+                                                                "Save"
+                                                            }
+                                                        }
+                                                    } else {
+                                                        h3 {
+                                                            "{summary.name}"
+                                                            if playing {
+                                                                span { class: "saved-game-current", " ▶ Playing now" }
+                                                            }
+                                                        }
+                                                    }
+                                                    p { class: "stats-line", "🕒 {played_ago(now, summary.last_played_ms)}" }
+                                                    p { class: "stats-line saved-game-id", title: "Enter this under 📥 Import to get the game back in another browser", "🔑 {session_id}" }
+                                                    if summary.started {
+                                                        p { class: "stats-line", "🎮 Round {summary.round_number}" }
+                                                        p { class: "stats-line", "🏆 {standings}" }
+                                                    } else {
+                                                        p { class: "stats-line", "🎮 Still setting up" }
+                                                    }
+                                                }
+                                                div { class: "saved-game-actions",
+                                                    button {
+                                                        class: "next-round-btn",
+                                                        onclick: {
+                                                            let session_id = session_id.clone();
+                                                            move |_| resume(session_id.clone())
+                                                        },
+                                                        "▶ Resume"
+                                                    }
+                                                    button {
+                                                        class: "view-scores-btn",
+                                                        onclick: {
+                                                            let session_id = session_id.clone();
+                                                            let name = game.save_name.clone();
+                                                            move |_| {
+                                                                new_name.set(name.clone());
+                                                                renaming.set(Some(session_id.clone()));
+                                                            }
+                                                        },
+                                                        "✏️ Rename"
+                                                    }
+                                                    button {
+                                                        class: "view-scores-btn",
+                                                        onclick: {
+                                                            let game = game.clone();
+                                                            move |_| {
+                                                                let copy = duplicate_game(&game, generate_session_id());
+                                                                // The copy carries on from rounds already in the stats
+                                                                copy_recorded_progress(&game.session_id, &copy.session_id);
+                                                                store_saved_game(&copy);
+                                                                revision += 1;
+                                                            }
+                                                        },
+                                                        "📄 Duplicate"
+                                                    }
+                                                    button {
+                                                        class: "view-scores-btn",
+                                                        onclick: {
+                                                            let session_id = session_id.clone();
+                                                            move |_| {
+                                                                let open = exporting().as_deref() == Some(session_id.as_str());
+                                                                exporting.set((!open).then(|| session_id.clone()));
+                                                            }
+                                                        },
+                                                        "📤 Export"
+                                                    }
+                                                    DeleteButton { session_id: session_id.clone(), deleting, revision }
+                                                }
+                                                if exporting().as_deref() == Some(session_id.as_str()) {
+                                                    ExportPanel {
+                                                        session_id: session_id.clone(),
+                                                        json: export_game(&game).unwrap_or_default(),
+                                                        code: export_code(&game).unwrap_or_default(),
+                                                        mid_round: round_in_play(&game),
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        Err(e) => rsx! {
+                                            div { class: "player-score-info",
+                                                h3 { "⚠️ Can't be restored" }
+                                                p { class: "stats-line", "{e}" }
+                                            }
+                                            div { class: "saved-game-actions",
+                                                DeleteButton { session_id: session_id.clone(), deleting, revision }
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    },
+                }
+
+                details { class: "scoring-section import-game",
+                    summary { "📥 Import a game from another device" }
+                    p { class: "hint",
+                        "Paste the code from the other device, or pick the file it saved. "
+                        "With server saves on, a game's 🔑 ID brings it back too, even after this browser was cleared."
+                    }
+                    textarea {
+                        class: "import-code",
+                        placeholder: "Game code or ID",
+                        value: "{import_code}",
+                        oninput: move |e| import_code.set(e.value()),
+                    }
+                    div { class: "saved-game-actions",
+                        button {
+                            class: "room-join-btn",
+                            disabled: import_code().trim().is_empty(),
+                            onclick: move |_| import(import_code()),
+                            "📥 Import"
+                        }
+                        label { class: "word-pack-upload",
+                            "📂 Open a file"
+                            input {
+                                r#type: "file",
+                                accept: ".json",
+                                onchange: move |evt| async move {
+                                    if let Some(file) = evt.files().into_iter().next() {
+                                        match file.read_string().await {
+                                            Ok(contents) => import(contents).await,
+                                            Err(e) => import_error.set(Some(format!("{}: {}", file.name(), e))),
+                                        }
+                                    }
+                                },
+                            }
+                        }
+                    }
+                    if let Some(error) = import_error() {
+                        p { class: "room-error", "⚠️ {error}" }
+                    }
+                }
+
+                div { class: "action-buttons",
+                    button {
+                        class: "new-game-btn",
+                        onclick: move |_| resume(generate_session_id()),
+                        "➕ New game"
+                    }
+                    Link { to: "/", class: "room-link", "← Back to the game" }
+                }
+            }
+        }
+    }
+}
+
+/// A saved game ready to move to another device, as a QR code, a code to copy or a file
+#[component]
+fn ExportPanel(session_id: String, json: String, code: String, mid_round: bool) -> Element {
+    // Compact JSON is the shortest form, so it has the best chance of fitting in a QR code
+    let qr = qr_svg(&json);
+    let file_name = format!("imposter-game-{}.json", session_id);
+
+    rsx! {
+        div { class: "export-panel",
+            if mid_round {
+                p { class: "hint",
+                    "🤫 This round is still being played, so its secret words are left out. "
+                    "After importing, the round is dealt again."
+                }
+            }
+            match qr {
+                Some(svg) => rsx! {
+                    div { class: "export-qr", dangerous_inner_html: "{svg}" }
+                    p { class: "hint", "Scan this on the other device, then paste what it reads into 📥 Import." }
+                },
+                None => rsx! {
+                    p { class: "hint", "This game has too much history for a QR code. Copy the code or download the file instead." }
+                },
+            }
+            div { class: "saved-game-actions",
+                button {
+                    class: "view-scores-btn",
+                    onclick: move |_| copy_to_clipboard(&code),
+                    "📋 Copy code"
+                }
+                button {
+                    class: "view-scores-btn",
+                    onclick: move |_| download(&file_name, &json),
+                    "⬇️ Download file"
+                }
+            }
+        }
+    }
+}
+
+/// Delete a saved game, asking first
+#[component]
+fn DeleteButton(session_id: String, mut deleting: Signal<Option<String>>, mut revision: Signal<u32>) -> Element {
+    if deleting().as_deref() == Some(session_id.as_str()) {
+        rsx! {
+            button {
+                class: "confirm-yes-btn",
+                onclick: move |_| {
+                    let session_id = session_id.clone();
+                    spawn(async move {
+                        delete_game_state(&session_id).await;
+                        deleting.set(None);
+                        revision += 1;
+                    });
+                },
+                "Delete"
+            }
+            button {
+                class: "confirm-no-btn",
+                onclick: move |_| deleting.set(None),
+                "Keep"
+            }
+        }
+    } else {
+        rsx! {
+            button {
+                class: "roster-remove",
+                title: "Delete this game",
+                onclick: move |_| deleting.set(Some(session_id.clone())),
+                "🗑️"
+            }
+        }
+    }
+}
@@ -1,19 +1,20 @@
 use dioxus::prelude::*;
-use crate::views::game::types::*;
-use crate::views::game::utils::get_random_starting_index;
+use crate::views::game::{dispatch, match_winners, start_new_game, types::*, GameAction, ManagePlayers};
+use std::cmp::Reverse;
 
 /// Screen showing all player scores
 #[component]
-pub fn GameScoreScreen(
-    players: Signal<Vec<Player>>,
-    round_number: Signal<i32>,
-    mut game_screen: Signal<GameScreen>,
-    mut cards: Signal<Vec<GameCard>>,
-    mut imposter_index: Signal<usize>,
-    mut starting_player_index: Signal<usize>,
-) -> Element {
-    let mut sorted_players = players();
-    sorted_players.sort_by(|a, b| b.score.cmp(&a.score));
+pub fn GameScoreScreen(state: Signal<GameState>) -> Element {
+    let GameState { players: mut sorted_players, round_number, .. } = state();
+    sorted_players.sort_by_key(|p| Reverse(p.score));
+    // Someone reached the target score: the match is over
+    let current = state();
+    let champions = match_winners(&current)
+        .into_iter()
+        .map(|i| current.players[i].name.clone())
+        .collect::<Vec<_>>()
+        .join(" & ");
+    let target = current.scoring.target_score.unwrap_or_default();
     let mut show_confirmation = use_signal(|| false);
 
     rsx! {
@@ -23,21 +24,13 @@ pub fn GameScoreScreen(
                 div { class: "confirmation-overlay",
                     div { class: "confirmation-dialog",
                         h2 { "⚠️ Start New Game?" }
-                        p { "All player scores will be reset. Are you sure?" }
+                        p { "Scores start again from zero. This game stays in 💾 Saved games, so you can come back to it." }
                         div { class: "confirmation-buttons",
                             button {
                                 class: "confirm-yes-btn",
                                 onclick: move |_| {
-                                    // Clear all game state for a completely fresh start
-                                    cards.set(Vec::new());
-                                    imposter_index.set(0);
-                                    
-                                    // Randomize starting player for new game
-                                    let player_count = players().len();
-                                    starting_player_index.set(get_random_starting_index(player_count));
-                                    
                                     show_confirmation.set(false);
-                                    game_screen.set(GameScreen::Setup);
+                                    start_new_game(state);
                                 },
                                 "Yes, Start New Game"
                             }
@@ -54,7 +47,7 @@ pub fn GameScoreScreen(
             }
             
             h1 { "🏆 Scoreboard" }
-            p { class: "round-info", "After Round {round_number()}" }
+            p { class: "round-info", "After Round {round_number}" }
             
             div { class: "scoreboard",
                 for (rank, player) in sorted_players.iter().enumerate() {
@@ -72,30 +65,37 @@ pub fn GameScoreScreen(
                 }
             }
             
+            if !champions.is_empty() {
+                div { class: "match-winner",
+                    h2 { "🏆 {champions} won the match!" }
+                    p { "First to {target} points" }
+                    p { class: "seed-info", "🎲 Seed {current.game_seed}" }
+                }
+            } else {
+                // Latecomers and early leavers only matter while there's another round to play
+                ManagePlayers { state }
+            }
+
             div { class: "action-buttons",
+                // Nothing left to play once someone reaches the target score
+                if champions.is_empty() {
+                    button {
+                        class: "next-round-btn",
+                        onclick: move |_| {
+                            dispatch(state, GameAction::NextRound);
+                        },
+                        "Play Next Round"
+                    }
+                }
+                
                 button {
-                    class: "next-round-btn",
+                    class: "view-scores-btn",
                     onclick: move |_| {
-                        // Reset all player states for new round
-                        let mut updated_players = players();
-                        for player in updated_players.iter_mut() {
-                            player.is_eliminated = false; // Reset eliminations for new round
-                        }
-                        players.set(updated_players);
-                        cards.set(Vec::new());
-                        round_number.set(round_number() + 1);
-                        
-                        // Rotate starting player for next round
-                        let player_count = players().len();
-                        if player_count > 0 {
-                            starting_player_index.set((starting_player_index() + 1) % player_count);
-                        }
-                        
-                        game_screen.set(GameScreen::CategorySelection);
+                        dispatch(state, GameAction::ViewHistory);
                     },
-                    "Play Next Round"
+                    "📜 History"
                 }
-                
+
                 button {
                     class: "new-game-btn",
                     onclick: move |_| {
@@ -1,28 +1,65 @@
 use dioxus::prelude::*;
-use crate::views::game::types::{Player, GameScreen};
-use crate::views::game::utils::get_random_starting_index;
+use crate::views::game::{
+    dispatch, duplicate_names, imposter_count_for, load_player_registry, load_rosters, max_imposters, remove_roster,
+    requested_player_count, rng::parse_seed, roster_from_setup, save_roster, types::*, GameAction, MAX_PLAYERS,
+    MIN_PLAYERS,
+};
+
+/// A labelled number in one of the setup settings, with how to write it back
+type SettingField<S, T> = (&'static str, T, fn(&mut S, T));
 
 #[component]
-pub fn SetupScreen(
-    mut player_count_input: Signal<String>,
-    mut player_names: Signal<Vec<String>>,
-    mut players: Signal<Vec<Player>>,
-    mut game_screen: Signal<GameScreen>,
-    mut round_number: Signal<i32>,
-    mut starting_player_index: Signal<usize>,
-) -> Element {
-    let player_count = player_count_input().parse::<usize>().unwrap_or(3).max(3).min(10);
-    
+pub fn SetupScreen(state: Signal<GameState>) -> Element {
+    let player_count_input = state.read().player_count_input.clone();
+    let player_cap = state.read().player_cap;
+    let player_count = requested_player_count(&player_count_input, player_cap);
+    let current_names = state.read().player_names.clone();
+    let current_ids = state.read().player_ids.clone();
+    let duplicates = duplicate_names(&current_names);
+    // Players seen before in this browser, so their stats carry on under the same ID
+    let registry = use_hook(load_player_registry);
+    let known_names: Vec<String> = registry.players.values().map(|r| r.name.clone()).collect();
+    let imposter_count = state.read().imposter_count;
+    let mr_white_count = state.read().mr_white_count;
+    let scoring = state.read().scoring.clone();
+    let last_game_seed = state.read().last_game_seed;
+    let preset = ScoringPreset::matching(&scoring);
+    // Each editable points value, with how to write it back
+    let points_fields: [SettingField<ScoringRules, i32>; 7] = [
+        ("Civilians win", scoring.civilian_win, |r, v| r.civilian_win = v),
+        ("Imposters win", scoring.imposter_survival, |r, v| r.imposter_survival = v),
+        ("Bonus per vote survived", scoring.survival_bonus, |r, v| r.survival_bonus = v),
+        ("Penalty for evicting a civilian", scoring.civilian_eviction_penalty, |r, v| r.civilian_eviction_penalty = v),
+        ("Mr. White guesses the word", scoring.mr_white_guess, |r, v| r.mr_white_guess = v),
+        ("Last chance guess", scoring.last_chance, |r, v| r.last_chance = v),
+        ("Last chance, close guess", scoring.last_chance_close, |r, v| r.last_chance_close = v),
+    ];
+    let timers = state.read().timers.clone();
+    // Seconds for each timed phase, with how to write it back
+    let timer_fields: [SettingField<TimerSettings, u32>; 3] = [
+        ("Looking at each card", timers.card_secs, |t, v| t.card_secs = v),
+        ("Discussion", timers.discussion_secs, |t, v| t.discussion_secs = v),
+        ("Secret vote", timers.voting_secs, |t, v| t.voting_secs = v),
+    ];
+    let timed = timer_fields.iter().any(|&(_, secs, _)| secs > 0);
+    let auto_imposters = imposter_count_for(player_count, None);
+    // Hosting a room already - don't offer to start another one
+    let in_room = try_consume_context::<Coroutine<GameAction>>().is_some();
+    let mut join_code = use_signal(String::new);
+    let mut seed_input = use_signal(|| state.peek().seed.map(|s| s.to_string()).unwrap_or_default());
+    let mut rosters = use_signal(load_rosters);
+    let mut roster_name = use_signal(String::new);
+    let can_save_roster = roster_from_setup(&roster_name(), &state.read()).is_some();
+    // Seat being dragged to a new place
+    let mut dragging = use_signal(|| None::<usize>);
+
     // Initialize player names if needed - ensure this happens before rendering
-    let mut current_names = player_names();
-    if current_names.len() != player_count {
-        let mut names = vec![String::new(); player_count];
-        for i in 0..player_count.min(current_names.len()) {
-            names[i] = current_names[i].clone();
+    use_effect(move || {
+        let input = state.read().player_count_input.clone();
+        if state.read().player_names.len() != requested_player_count(&input, state.read().player_cap) {
+            dispatch(state, GameAction::SetPlayerCount(input));
         }
-        current_names = names.clone();
-        player_names.set(names);
-    }
+    });
 
     rsx! {
         div { class: "setup-screen",
@@ -30,70 +67,368 @@ pub fn SetupScreen(
                 h1 { "🎮 Ultimate Imposter" }
                 p { class: "subtitle", "The Social Deduction Game" }
             }
-            
+
+            div { class: "roster-section",
+                h2 { "📋 Saved Groups" }
+                if !rosters.read().is_empty() {
+                    div { class: "roster-list",
+                        for roster in rosters() {
+                            div {
+                                key: "{roster.name}",
+                                class: "roster-chip",
+                                button {
+                                    class: "roster-use",
+                                    onclick: {
+                                        let registry = registry.clone();
+                                        let players = roster.players.clone();
+                                        move |_| {
+                                            // Link anyone who has played since the group was saved
+                                            let players = players
+                                                .iter()
+                                                .cloned()
+                                                .map(|mut player| {
+                                                    if player.id.is_empty() {
+                                                        if let Some(record) = registry.find_by_name(&player.name) {
+                                                            player.id = record.id.clone();
+                                                        }
+                                                    }
+                                                    player
+                                                })
+                                                .collect();
+                                            dispatch(state, GameAction::UseRoster(players));
+                                        }
+                                    },
+                                    "{roster.name} ({roster.players.len()})"
+                                }
+                                button {
+                                    class: "roster-remove",
+                                    title: "Forget this group",
+                                    onclick: {
+                                        let name = roster.name.clone();
+                                        move |_| rosters.set(remove_roster(&name))
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "roster-save",
+                    input {
+                        r#type: "text",
+                        placeholder: "Save these players as... e.g. Friday crew",
+                        value: "{roster_name}",
+                        oninput: move |e| roster_name.set(e.value()),
+                    }
+                    button {
+                        class: "room-join-btn",
+                        disabled: !can_save_roster,
+                        onclick: move |_| {
+                            if let Some(roster) = roster_from_setup(&roster_name(), &state.peek()) {
+                                rosters.set(save_roster(roster));
+                                roster_name.set(String::new());
+                            }
+                        },
+                        "💾 Save"
+                    }
+                }
+            }
+
             div { class: "player-count-section",
-                label { 
+                label {
                     "👥 Number of Players"
-                    span { class: "hint", "(minimum 3)" }
+                    span { class: "hint", "({MIN_PLAYERS}-{player_cap})" }
                 }
                 input {
                     r#type: "number",
-                    min: "3",
-                    max: "10",
+                    min: "{MIN_PLAYERS}",
+                    max: "{player_cap}",
                     value: "{player_count_input}",
                     oninput: move |e| {
-                        player_count_input.set(e.value());
+                        dispatch(state, GameAction::SetPlayerCount(e.value()));
+                    }
+                }
+            }
+
+            div { class: "setup-option",
+                label {
+                    "🎉 Player limit"
+                    span { class: "hint", "(raise it for parties)" }
+                }
+                select {
+                    onchange: move |e| {
+                        if let Ok(cap) = e.value().parse() {
+                            dispatch(state, GameAction::SetPlayerCap(cap));
+                        }
+                    },
+                    for n in MIN_PLAYERS..=MAX_PLAYERS {
+                        option { value: "{n}", selected: n == player_cap, "{n}" }
                     }
                 }
             }
-            
+
             div { class: "player-names-section",
                 h2 { "✏️ Player Names" }
+                p { class: "hint", "Drag a seat number (or use the arrows) to change the seating order." }
                 div { class: "player-inputs-grid",
                     for i in 0..player_count {
-                        div { class: "player-input",
-                            span { class: "player-number", "{i + 1}" }
+                        div {
+                            class: if duplicates.contains(&i) { "player-input duplicate" } else { "player-input" },
+                            ondragover: move |e| e.prevent_default(),
+                            ondrop: move |e| {
+                                e.prevent_default();
+                                let from = dragging();
+                                dragging.set(None);
+                                if let Some(from) = from.filter(|&from| from != i) {
+                                    dispatch(state, GameAction::MovePlayer { from, to: i });
+                                }
+                            },
+                            span {
+                                class: "player-number",
+                                draggable: "true",
+                                title: "Drag to change seats",
+                                ondragstart: move |_| dragging.set(Some(i)),
+                                ondragend: move |_| dragging.set(None),
+                                "{i + 1}"
+                            }
                             input {
                                 r#type: "text",
                                 placeholder: "Enter name...",
+                                list: "known-players",
                                 value: "{current_names.get(i).cloned().unwrap_or_default()}",
-                                oninput: move |e| {
-                                    let mut names = player_names();
-                                    // Ensure the vector is large enough
-                                    while names.len() <= i {
-                                        names.push(String::new());
+                                oninput: {
+                                    let registry = registry.clone();
+                                    move |e: FormEvent| {
+                                        let name = e.value();
+                                        let known = registry.find_by_name(&name).map(|r| r.id.clone());
+                                        dispatch(state, GameAction::SetPlayerName { index: i, name });
+                                        if known.is_some() {
+                                            dispatch(state, GameAction::SetPlayerId { index: i, id: known });
+                                        }
                                     }
-                                    names[i] = e.value();
-                                    player_names.set(names);
                                 }
                             }
+                            if current_ids.get(i).is_some_and(|id| registry.players.contains_key(id)) {
+                                span { class: "known-player", title: "Stats carry on from earlier games", "📊" }
+                            }
+                            if duplicates.contains(&i) {
+                                span { class: "duplicate-name", title: "Someone else already has this name", "⚠️" }
+                            }
+                            div { class: "seat-moves",
+                                button {
+                                    class: "seat-move",
+                                    title: "Move up a seat",
+                                    disabled: i == 0,
+                                    onclick: move |_| {
+                                        dispatch(state, GameAction::MovePlayer { from: i, to: i.saturating_sub(1) });
+                                    },
+                                    "▲"
+                                }
+                                button {
+                                    class: "seat-move",
+                                    title: "Move down a seat",
+                                    disabled: i + 1 >= player_count,
+                                    onclick: move |_| {
+                                        dispatch(state, GameAction::MovePlayer { from: i, to: i + 1 });
+                                    },
+                                    "▼"
+                                }
+                            }
+                        }
+                    }
+                }
+                datalist { id: "known-players",
+                    for name in known_names {
+                        option { value: "{name}" }
+                    }
+                }
+            }
+
+            div { class: "setup-option",
+                label {
+                    "🎭 Imposters per round"
+                }
+                select {
+                    value: imposter_count.map(|n| n.to_string()).unwrap_or_default(),
+                    onchange: move |e| {
+                        dispatch(state, GameAction::SetImposterCount(e.value().parse().ok()));
+                    },
+                    option { value: "", "Auto ({auto_imposters})" }
+                    // No imposters at all only works with Mr. White in their place
+                    if mr_white_count > 0 {
+                        option { value: "0", "0" }
+                    }
+                    for n in 1..=max_imposters(player_count) {
+                        option { value: "{n}", "{n}" }
+                    }
+                }
+            }
+
+            div { class: "setup-option",
+                label {
+                    "🕴️ Mr. White"
+                    span { class: "hint", "(dealt no word at all)" }
+                }
+                select {
+                    value: "{mr_white_count}",
+                    onchange: move |e| {
+                        dispatch(state, GameAction::SetMrWhiteCount(e.value().parse().unwrap_or(0)));
+                    },
+                    option { value: "0", "None" }
+                    for n in 1..=max_imposters(player_count) {
+                        option { value: "{n}", "{n}" }
+                    }
+                }
+            }
+
+            details { class: "scoring-section",
+                summary {
+                    "🏅 Scoring: "
+                    {preset.map(|p| p.name()).unwrap_or("Custom")}
+                }
+                div { class: "setup-option",
+                    select {
+                        value: preset.map(|p| p.name()).unwrap_or_default(),
+                        onchange: move |e| {
+                            if let Some(preset) = ScoringPreset::ALL.into_iter().find(|p| p.name() == e.value()) {
+                                dispatch(state, GameAction::SetScoring(preset.rules()));
+                            }
+                        },
+                        if preset.is_none() {
+                            option { value: "", "Custom" }
+                        }
+                        for preset in ScoringPreset::ALL {
+                            option { value: preset.name(), "{preset.name()}" }
+                        }
+                    }
+                }
+                div { class: "scoring-grid",
+                    for (label, value, set) in points_fields {
+                        label { "{label}" }
+                        input {
+                            r#type: "number",
+                            value: "{value}",
+                            oninput: move |e| {
+                                if let Ok(points) = e.value().parse() {
+                                    let mut rules = state.peek().scoring.clone();
+                                    set(&mut rules, points);
+                                    dispatch(state, GameAction::SetScoring(rules));
+                                }
+                            }
+                        }
+                    }
+                    label { "Points to win the match" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        placeholder: "No limit",
+                        value: scoring.target_score.map(|t| t.to_string()).unwrap_or_default(),
+                        oninput: move |e| {
+                            let mut rules = state.peek().scoring.clone();
+                            rules.target_score = e.value().parse().ok().filter(|&t: &i32| t > 0);
+                            dispatch(state, GameAction::SetScoring(rules));
+                        }
+                    }
+                    label { "Evicted players score when their side wins" }
+                    input {
+                        r#type: "checkbox",
+                        checked: scoring.whole_team_scores,
+                        oninput: move |e| {
+                            let mut rules = state.peek().scoring.clone();
+                            rules.whole_team_scores = e.value() == "true";
+                            dispatch(state, GameAction::SetScoring(rules));
+                        }
+                    }
+                }
+            }
+
+            details { class: "scoring-section",
+                summary {
+                    "⏱️ Timers: "
+                    if timed { "On" } else { "Off" }
+                }
+                p { class: "hint", "Seconds for each phase, 0 for no limit. Time running out moves the game on." }
+                div { class: "scoring-grid",
+                    for (label, secs, set) in timer_fields {
+                        label { "{label}" }
+                        input {
+                            r#type: "number",
+                            min: "0",
+                            value: "{secs}",
+                            oninput: move |e| {
+                                if let Ok(secs) = e.value().parse() {
+                                    let mut timers = state.peek().timers.clone();
+                                    set(&mut timers, secs);
+                                    dispatch(state, GameAction::SetTimers(timers));
+                                }
+                            }
+                        }
+                    }
+                    label { "Beep as time runs out" }
+                    input {
+                        r#type: "checkbox",
+                        checked: timers.sound,
+                        oninput: move |e| {
+                            let mut timers = state.peek().timers.clone();
+                            timers.sound = e.value() == "true";
+                            dispatch(state, GameAction::SetTimers(timers));
                         }
                     }
                 }
             }
-            
+
+            div { class: "seed-section",
+                label {
+                    "🎲 Seed"
+                    span { class: "hint", "(optional - same seed, same game)" }
+                }
+                input {
+                    r#type: "text",
+                    placeholder: "Random",
+                    value: "{seed_input}",
+                    oninput: move |e| {
+                        seed_input.set(e.value());
+                        dispatch(state, GameAction::SetSeed(parse_seed(&e.value())));
+                    }
+                }
+                if let Some(seed) = last_game_seed {
+                    p { class: "seed-info", "🎲 The last game's seed was {seed}" }
+                }
+            }
+
             button {
                 class: "start-game-btn",
                 onclick: move |_| {
-                    let names = player_names();
-                    if names.iter().all(|n| !n.trim().is_empty()) {
-                        let new_players: Vec<Player> = names.iter().map(|name| Player {
-                            name: name.clone(),
-                            score: 0,
-                            is_eliminated: false,
-                        }).collect();
-                        players.set(new_players.clone());
-                        round_number.set(1);
-                        
-                        // Randomize starting player for new game
-                        starting_player_index.set(get_random_starting_index(new_players.len()));
-                        
-                        game_screen.set(GameScreen::CategorySelection);
-                    }
+                    dispatch(state, GameAction::StartGame);
                 },
                 "🚀 Start Game"
             }
+
+            if !in_room {
+                div { class: "room-links",
+                    Link { to: "/games", class: "room-link", "💾 Saved games" }
+                    Link { to: "/stats", class: "room-link", "📊 Player stats" }
+                    Link { to: "/host", class: "room-link", "📡 Play with everyone on their own phone" }
+                    div { class: "room-join",
+                        input {
+                            r#type: "text",
+                            placeholder: "Room code",
+                            value: "{join_code}",
+                            oninput: move |e| join_code.set(e.value()),
+                        }
+                        button {
+                            class: "room-join-btn",
+                            onclick: move |_| {
+                                let code = join_code().trim().to_ascii_uppercase();
+                                if !code.is_empty() {
+                                    navigator().push(format!("/join/{}", code));
+                                }
+                            },
+                            "Join"
+                        }
+                    }
+                }
+            }
         }
     }
 }
-
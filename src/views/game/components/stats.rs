@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+use crate::views::game::{load_player_registry, types::*};
+use std::cmp::Reverse;
+
+const _GAME_CSS: Asset = asset!("/assets/styling/game.css");
+const FAVOURITES_SEPARATOR: &str = " · ";
+
+/// What the leaderboard is ranked by
+#[derive(Clone, Copy, PartialEq)]
+enum Ranking {
+    GamesPlayed,
+    ImposterWinRate,
+    CorrectEvictions,
+    WronglyEvicted,
+}
+
+impl Ranking {
+    const ALL: [Ranking; 4] =
+        [Ranking::GamesPlayed, Ranking::ImposterWinRate, Ranking::CorrectEvictions, Ranking::WronglyEvicted];
+
+    fn label(self) -> &'static str {
+        match self {
+            Ranking::GamesPlayed => "Games played",
+            Ranking::ImposterWinRate => "Imposter win rate",
+            Ranking::CorrectEvictions => "Correct evictions",
+            Ranking::WronglyEvicted => "Times wrongly evicted",
+        }
+    }
+
+    /// Highest first
+    fn sort(self, records: &mut [PlayerRecord]) {
+        let rate = |r: &PlayerRecord| r.imposter_win_rate().unwrap_or(-1.0);
+        match self {
+            Ranking::GamesPlayed => records.sort_by_key(|r| Reverse(r.games_played)),
+            Ranking::ImposterWinRate => records.sort_by(|a, b| rate(b).total_cmp(&rate(a))),
+            Ranking::CorrectEvictions => records.sort_by_key(|r| Reverse(r.correct_evictions)),
+            Ranking::WronglyEvicted => records.sort_by_key(|r| Reverse(r.wrongly_evicted)),
+        }
+    }
+}
+
+fn imposter_summary(record: &PlayerRecord) -> String {
+    match record.imposter_win_rate() {
+        Some(rate) => format!("Imposter {}× · won {:.0}%", record.times_imposter, rate * 100.0),
+        None => "Never the imposter yet".to_string(),
+    }
+}
+
+/// Lifetime stats for everyone who has played in this browser
+#[component]
+pub fn Stats() -> Element {
+    let registry = use_hook(load_player_registry);
+    let mut ranking = use_signal(|| Ranking::GamesPlayed);
+    let mut records: Vec<PlayerRecord> = registry.players.values().cloned().collect();
+    ranking().sort(&mut records);
+
+    rsx! {
+        document::Stylesheet { href: _GAME_CSS }
+        div { class: "game-container",
+            div { class: "score-screen stats-screen",
+                h1 { "📊 Player Stats" }
+
+                if records.is_empty() {
+                    p { class: "instruction", "Nobody has played yet. Stats appear here once a game has started." }
+                } else {
+                    div { class: "setup-option",
+                        label { "Rank by" }
+                        select {
+                            onchange: move |e| {
+                                if let Some(r) = Ranking::ALL.into_iter().find(|r| r.label() == e.value()) {
+                                    ranking.set(r);
+                                }
+                            },
+                            for r in Ranking::ALL {
+                                option { value: r.label(), selected: r == ranking(), "{r.label()}" }
+                            }
+                        }
+                    }
+
+                    div { class: "scoreboard",
+                        for (rank, record) in records.into_iter().enumerate() {
+                            div {
+                                key: "{record.id}",
+                                class: "score-card stats-card",
+                                div { class: "rank", "#{rank + 1}" }
+                                div { class: "player-score-info",
+                                    h3 { "{record.name}" }
+                                    p { class: "stats-line",
+                                        "🎮 {record.games_played} games · {record.rounds_played} rounds"
+                                    }
+                                    p { class: "stats-line", "🎭 {imposter_summary(&record)}" }
+                                    p { class: "stats-line",
+                                        "🎯 {record.correct_evictions} correct evictions · 🚪 wrongly evicted {record.wrongly_evicted}×"
+                                    }
+                                    if !record.categories.is_empty() {
+                                        p { class: "stats-line", "❤️ {record.favourite_categories(3).join(FAVOURITES_SEPARATOR)}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "action-buttons",
+                    Link { to: "/", class: "room-link", "← Back to the game" }
+                }
+            }
+        }
+    }
+}
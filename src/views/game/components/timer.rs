@@ -0,0 +1,132 @@
+use dioxus::prelude::*;
+use crate::views::game::{dispatch, now_ms, sleep_ms, types::*, GameAction};
+
+/// Seconds left when the countdown turns red
+const URGENT_SECS: i64 = 10;
+/// Seconds left when it starts beeping, if sound is on
+const BEEP_SECS: i64 = 5;
+
+/// A short tone through Web Audio. The context is kept on `window` so every beep reuses it.
+const BEEP_SCRIPT: &str = r#"
+    const Context = window.AudioContext || window.webkitAudioContext;
+    if (Context) {
+        const audio = window.__imposterAudio = window.__imposterAudio || new Context();
+        const tone = audio.createOscillator();
+        const volume = audio.createGain();
+        tone.frequency.value = FREQUENCY;
+        volume.gain.value = 0.1;
+        tone.connect(volume);
+        volume.connect(audio.destination);
+        tone.start();
+        tone.stop(audio.currentTime + SECONDS);
+    }
+"#;
+
+fn beep(frequency: u32, seconds: f32) {
+    let script = BEEP_SCRIPT
+        .replace("FREQUENCY", &frequency.to_string())
+        .replace("SECONDS", &seconds.to_string());
+    let _ = document::eval(&script);
+}
+
+/// Countdown for the current phase, if the host timed it.
+///
+/// The device driving the game starts the clock when the screen comes up and tells the engine
+/// when it runs out. `readonly` copies (a player's phone in a room) only show it.
+#[component]
+pub fn PhaseTimerBar(state: Signal<GameState>, #[props(default)] readonly: bool) -> Element {
+    let mut now = use_signal(now_ms);
+    // Deadline a TimeUp has been sent for, so a slow room server doesn't get it twice
+    let mut fired = use_signal(|| None::<i64>);
+    let mut last_beep = use_signal(|| None::<i64>);
+
+    use_future(move || async move {
+        loop {
+            sleep_ms(250).await;
+            now.set(now_ms());
+        }
+    });
+
+    // Start each countdown as soon as its screen is up
+    use_effect(move || {
+        let waiting = state.read().timer.as_ref().is_some_and(|t| t.deadline_ms.is_none() && !t.paused && t.remaining_ms > 0);
+        if waiting && !readonly {
+            dispatch(state, GameAction::RunTimer { now_ms: now_ms() });
+        }
+    });
+
+    use_effect(move || {
+        let now = now();
+        let sound = state.read().timers.sound;
+        let Some(deadline) = state.read().timer.as_ref().and_then(|t| t.deadline_ms) else {
+            return;
+        };
+        let secs_left = ((deadline - now).max(0) + 999) / 1000;
+        if sound && secs_left <= BEEP_SECS && *last_beep.peek() != Some(secs_left) {
+            last_beep.set(Some(secs_left));
+            if secs_left == 0 { beep(440, 0.8) } else { beep(880, 0.1) }
+        }
+        if !readonly && now >= deadline && *fired.peek() != Some(deadline) {
+            fired.set(Some(deadline));
+            dispatch(state, GameAction::TimeUp { now_ms: now });
+        }
+    });
+
+    let Some(timer) = state().timer else {
+        return rsx! {};
+    };
+    if readonly && timer.deadline_ms.is_none() && !timer.paused {
+        // Not started yet - nothing to count down
+        return rsx! {};
+    }
+
+    let left = timer.remaining_at(now());
+    let secs = (left + 999) / 1000;
+    let clock = format!("{}:{:02}", secs / 60, secs % 60);
+    let percent = left * 100 / timer.duration_ms.max(1);
+    let paused = timer.paused;
+    let label = match timer.phase {
+        TimedPhase::CardReveal => "🃏 Card",
+        TimedPhase::Discussion => "💬 Discussion",
+        TimedPhase::Voting => "🗳️ Voting",
+    };
+
+    rsx! {
+        div {
+            class: if left == 0 {
+                "phase-timer expired"
+            } else if paused {
+                "phase-timer paused"
+            } else if secs <= URGENT_SECS {
+                "phase-timer urgent"
+            } else {
+                "phase-timer"
+            },
+            div { class: "timer-readout",
+                span { class: "timer-label", "{label}" }
+                if left == 0 {
+                    span { class: "timer-time", "⏰ Time's up!" }
+                } else {
+                    span { class: "timer-time", "{clock}" }
+                }
+                if !readonly && left > 0 {
+                    button {
+                        class: "timer-toggle",
+                        onclick: move |_| {
+                            let action = if paused {
+                                GameAction::RunTimer { now_ms: now_ms() }
+                            } else {
+                                GameAction::PauseTimer { now_ms: now_ms() }
+                            };
+                            dispatch(state, action);
+                        },
+                        if paused { "▶️ Resume" } else { "⏸️ Pause" }
+                    }
+                }
+            }
+            div { class: "timer-track",
+                div { class: "timer-fill", style: "width: {percent}%" }
+            }
+        }
+    }
+}
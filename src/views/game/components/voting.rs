@@ -1,17 +1,22 @@
 use dioxus::prelude::*;
-use crate::views::game::types::*;
+use crate::views::game::{dispatch, types::*, GameAction, PhaseTimerBar, VoteBreakdown};
 
 /// Voting screen where all players collectively decide who to evict
 #[component]
-pub fn VotingScreen(
-    mut players: Signal<Vec<Player>>,
-    mut game_screen: Signal<GameScreen>,
-    imposter_index: Signal<usize>,
-    mut cards: Signal<Vec<GameCard>>,
-    current_category: Signal<Option<(String, String)>>,
-    starting_player_index: Signal<usize>,
-) -> Element {
-    let player_list = players();
+pub fn VotingScreen(state: Signal<GameState>) -> Element {
+    let GameState {
+        players: player_list,
+        starting_player_index,
+        ballot_mode,
+        vote_tallies,
+        round_number,
+        ..
+    } = state();
+    // A tied vote that evicted nobody sends everyone back here, so show how it went
+    let tied_vote = vote_tallies
+        .last()
+        .filter(|t| matches!(t.outcome, VoteOutcome::NoEviction { .. }) && t.round_number + 1 == round_number)
+        .cloned();
     let mut show_restart_confirmation = use_signal(|| false);
     
     // Only show non-eliminated players
@@ -23,7 +28,7 @@ pub fn VotingScreen(
     
     // Rotate voting order based on starting_player_index
     let total_players = player_list.len();
-    let start_idx = starting_player_index() % total_players;
+    let start_idx = starting_player_index % total_players;
     
     // Create rotated player list for voting display
     let mut rotated_player_data: Vec<(usize, String)> = Vec::new();
@@ -44,10 +49,8 @@ pub fn VotingScreen(
                             button {
                                 class: "confirm-yes-btn",
                                 onclick: move |_| {
-                                    // Reset round state
-                                    cards.set(Vec::new());
                                     show_restart_confirmation.set(false);
-                                    game_screen.set(GameScreen::CategorySelection);
+                                    dispatch(state, GameAction::RestartRound);
                                 },
                                 "Yes, Restart Round"
                             }
@@ -64,6 +67,12 @@ pub fn VotingScreen(
             }
             
             h1 { "🗳️ Discussion & Voting" }
+
+            PhaseTimerBar { state }
+
+            if let Some(tally) = tied_vote {
+                VoteBreakdown { tally, names: player_list.iter().map(|p| p.name.clone()).collect() }
+            }
             
             div { class: "voting-instructions",
                 p { "💬 Discussion order (everyone explains their word):" }
@@ -76,25 +85,35 @@ pub fn VotingScreen(
                         }
                     }
                 }
-                p { class: "hint", "After discussion, tap on the player card you all agreed to evict." }
+                if ballot_mode {
+                    p { class: "hint", "After discussion, everyone votes in secret." }
+                } else {
+                    p { class: "hint", "After discussion, tap on the player card you all agreed to evict." }
+                }
             }
             
-            div { class: "players-voting-list",
-                for &(player_idx, ref player_name) in rotated_player_data.iter() {
-                    div { class: "player-voting-card",
-                        div { class: "player-info",
-                            h3 { "{player_name}" }
-                        }
-                        button {
-                            class: "evict-btn",
-                            onclick: move |_| {
-                                let was_imposter = player_idx == imposter_index();
-                                game_screen.set(GameScreen::Elimination { 
-                                    eliminated_index: player_idx,
-                                    was_imposter 
-                                });
-                            },
-                            "Evict"
+            if ballot_mode {
+                button {
+                    class: "start-ballot-btn",
+                    onclick: move |_| {
+                        dispatch(state, GameAction::OpenBallot);
+                    },
+                    "🗳️ Start Secret Vote"
+                }
+            } else {
+                div { class: "players-voting-list",
+                    for &(player_idx, ref player_name) in rotated_player_data.iter() {
+                        div { class: "player-voting-card",
+                            div { class: "player-info",
+                                h3 { "{player_name}" }
+                            }
+                            button {
+                                class: "evict-btn",
+                                onclick: move |_| {
+                                    dispatch(state, GameAction::Evict { player_index: player_idx });
+                                },
+                                "Evict"
+                            }
                         }
                     }
                 }
@@ -0,0 +1,76 @@
+use dioxus::prelude::*;
+use crate::views::game::{dispatch, types::*, GameAction};
+
+/// Evicted Mr. White's one chance to steal the round by naming the civilian word
+#[component]
+pub fn MrWhiteGuessScreen(
+    player_index: usize,
+    state: Signal<GameState>,
+) -> Element {
+    let GameState { players, scoring, .. } = state();
+    let player_name = players.get(player_index).map(|p| p.name.clone()).unwrap_or_default();
+
+    rsx! {
+        div { class: "elimination-screen",
+            h1 { class: "mr-white-caught", "🕴️ Mr. White Evicted!" }
+            div { class: "elimination-result mr-white-chance",
+                p { class: "eliminated-player", "{player_name} never had a word..." }
+                p { class: "result-message",
+                    "One guess at the civilian word steals the round for +{scoring.mr_white_guess} points"
+                }
+            }
+            GuessInput { state }
+        }
+    }
+}
+
+/// Last chance rule: the evicted imposter names the civilian word for a consolation prize
+#[component]
+pub fn ImposterGuessScreen(
+    player_index: usize,
+    state: Signal<GameState>,
+) -> Element {
+    let GameState { players, scoring, .. } = state();
+    let player_name = players.get(player_index).map(|p| p.name.clone()).unwrap_or_default();
+
+    rsx! {
+        div { class: "elimination-screen",
+            h1 { class: "imposter-found", "🎯 Last Chance!" }
+            div { class: "elimination-result celebration",
+                p { class: "eliminated-player success", "🎭 {player_name} was an IMPOSTER!" }
+                p { class: "result-message",
+                    "Name the civilian word for +{scoring.last_chance} points (+{scoring.last_chance_close} if it's close)"
+                }
+            }
+            GuessInput { state }
+        }
+    }
+}
+
+/// Text box and button that submit a single guess
+#[component]
+fn GuessInput(state: Signal<GameState>) -> Element {
+    let mut guess = use_signal(String::new);
+
+    rsx! {
+        div { class: "word-guess",
+            input {
+                r#type: "text",
+                placeholder: "The civilian word is...",
+                value: "{guess}",
+                oninput: move |e| guess.set(e.value()),
+            }
+        }
+
+        div { class: "action-buttons",
+            button {
+                class: "continue-btn",
+                disabled: guess().trim().is_empty(),
+                onclick: move |_| {
+                    dispatch(state, GameAction::GuessWord { guess: guess() });
+                },
+                "Lock In Guess"
+            }
+        }
+    }
+}
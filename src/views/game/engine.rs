@@ -0,0 +1,1572 @@
+use crate::views::game::guess::{match_guess, synonyms_for};
+use crate::views::game::history::{played_rounds, PlayedRound};
+use crate::views::game::types::{
+    CardType, GameEvent, GameScreen, GameState, GuessMatch, PhaseTimer, Player, RosterPlayer, ScoringRules, StartingScore,
+    TieBreak, TimedPhase, TimerSettings, VoteOutcome, VoteTally, WordGuess,
+};
+use crate::views::game::rng::{entropy_seed, uniform_index, EntropyError, SeededRng};
+use crate::views::game::utils::{
+    find_category, generate_cards_for_category, get_random_starting_index, remaining_pair_count, DealtCards,
+    RoleCounts,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+// ============================================================================
+// Game Engine - all game rules, independent of any UI
+// ============================================================================
+
+pub const MIN_PLAYERS: usize = 3;
+/// Most players the setup screen can be raised to
+pub const MAX_PLAYERS: usize = 20;
+
+/// Everything a player (or host) can do to move the game forward
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GameAction {
+    /// Update the raw "number of players" field on the setup screen
+    SetPlayerCount(String),
+    /// Update the name typed into a setup slot
+    SetPlayerName { index: usize, name: String },
+    /// Link a setup slot to a known player's stats, or `None` to start them afresh
+    SetPlayerId { index: usize, id: Option<String> },
+    /// Move a player to another seat, shifting everyone in between
+    MovePlayer { from: usize, to: usize },
+    /// Fill every setup slot from a saved group, raising the player cap if it has to
+    UseRoster(Vec<RosterPlayer>),
+    /// Set the most players the setup screen allows
+    SetPlayerCap(usize),
+    /// Fix the seed for the next game, or `None` for a fresh random one
+    SetSeed(Option<u64>),
+    /// Set how many imposters each round has, or `None` to scale with the player count
+    SetImposterCount(Option<usize>),
+    /// Set how many players are dealt no word at all each round
+    SetMrWhiteCount(usize),
+    /// Replace the points table for this game
+    SetScoring(ScoringRules),
+    /// Set how long each timed phase lasts
+    SetTimers(TimerSettings),
+    /// Create the players from the entered names and go to category selection
+    StartGame,
+    /// Choose the category for the next round
+    PickCategory { category_id: String },
+    /// Turn a word pack's categories on or off for this game
+    SetPackEnabled { pack_id: String, enabled: bool },
+    /// Leave the category reveal and choose again
+    BackToCategories,
+    /// Toggle hard mode (imposter isn't told they are the imposter)
+    SetHardMode(bool),
+    /// Toggle fair deal (favour players who have been the imposter least)
+    SetFairDeal(bool),
+    /// Fair deal: most rounds in a row one player can be the imposter, 0 for no cap
+    SetMaxImposterStreak(u32),
+    /// Toggle the last chance rule (evicted imposters may guess the civilian word)
+    SetLastChance(bool),
+    /// Deal the cards and hand the device to the first player
+    StartRound,
+    /// Go back from the first card to the category reveal
+    BackToCategoryReveal,
+    /// The current player has seen their card; pass to the next one
+    RevealCard,
+    /// Everyone has seen their card, start the discussion.
+    /// In a room every player has their own phone, so the host may skip ahead.
+    ProceedToVoting,
+    /// Toggle secret ballots (everyone votes in turn) instead of one tap for the group
+    SetBallotMode(bool),
+    /// Choose what happens when a secret vote is tied
+    SetTieBreak(TieBreak),
+    /// The group agreed to evict this player
+    Evict { player_index: usize },
+    /// Discussion is over, start the secret vote
+    OpenBallot,
+    /// One player's secret vote. Counted automatically once everyone still in has voted.
+    CastVote { voter: usize, target: usize },
+    /// Throw away the votes so far and go back to the discussion
+    CancelBallot,
+    /// Apply the eviction shown on the elimination screen
+    ConfirmElimination,
+    /// An evicted Mr. White's (or last chance imposter's) one guess at the civilian word
+    GuessWord { guess: String },
+    /// Throw away the current round and pick a category again
+    RestartRound,
+    /// Show the scoreboard
+    ViewScores,
+    /// Look back over the rounds played this session
+    ViewHistory,
+    /// Reset eliminations and start the next round
+    NextRound,
+    /// Go back to setup for a completely new game
+    NewGame,
+    /// Seat a latecomer between rounds, linked to a known player's stats if `id` is set
+    AddPlayer { name: String, id: Option<String>, score: StartingScore },
+    /// Take someone who has left out of the game between rounds
+    RemovePlayer { player_index: usize },
+    /// Start the current phase's countdown, or pick it up again after a pause
+    RunTimer { now_ms: i64 },
+    /// Stop the countdown, keeping the time left
+    PauseTimer { now_ms: i64 },
+    /// The countdown has run out: move the game on as if the group had
+    TimeUp { now_ms: i64 },
+}
+
+/// Why an action was rejected
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameError {
+    /// The action doesn't make sense on the current screen
+    InvalidAction { action: &'static str, screen: &'static str },
+    /// Player count outside of the allowed range
+    InvalidPlayerCount(usize),
+    /// Too many imposters for the number of players
+    InvalidImposterCount(usize),
+    /// Neither imposters nor Mr. White would be dealt, so there is nobody to find
+    NoSecretRoles,
+    /// A player slot was left blank
+    EmptyPlayerName(usize),
+    /// Two players have the same name, so votes and scores couldn't tell them apart
+    DuplicatePlayerName(usize),
+    /// No player at this index
+    UnknownPlayer(usize),
+    /// The player has already been evicted this round
+    PlayerAlreadyEliminated(usize),
+    /// This player has already voted in the current ballot
+    AlreadyVoted(usize),
+    /// This player can't be voted for (themselves, or not in a revote)
+    NotACandidate(usize),
+    /// No category with this ID
+    UnknownCategory(String),
+    /// The category's word pack is turned off for this game
+    PackDisabled(String),
+    /// Trying to deal cards before a category was chosen
+    NoCategorySelected,
+    /// Couldn't get a random seed for a new game
+    Entropy(EntropyError),
+    /// Someone has reached the target score, so there are no more rounds to play
+    MatchOver,
+    /// The current phase isn't timed, or its countdown has already run out
+    NoTimer,
+    /// The countdown hasn't reached its deadline yet
+    TimeNotUp,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::InvalidAction { action, screen } => {
+                write!(f, "Cannot {} on the {} screen", action, screen)
+            }
+            GameError::InvalidPlayerCount(count) => write!(
+                f,
+                "Need between {} and {} players, got {}",
+                MIN_PLAYERS, MAX_PLAYERS, count
+            ),
+            GameError::InvalidImposterCount(count) => {
+                write!(f, "Can't have {} imposters: civilians must outnumber them", count)
+            }
+            GameError::NoSecretRoles => write!(f, "Deal at least one imposter or Mr. White"),
+            GameError::EmptyPlayerName(index) => write!(f, "Player {} has no name", index + 1),
+            GameError::DuplicatePlayerName(index) => {
+                write!(f, "Player {} has the same name as someone else", index + 1)
+            }
+            GameError::UnknownPlayer(index) => write!(f, "No player at index {}", index),
+            GameError::PlayerAlreadyEliminated(index) => {
+                write!(f, "Player {} is already eliminated", index + 1)
+            }
+            GameError::AlreadyVoted(index) => write!(f, "Player {} has already voted", index + 1),
+            GameError::NotACandidate(index) => write!(f, "Can't vote for player {}", index + 1),
+            GameError::UnknownCategory(id) => write!(f, "No category called {}", id),
+            GameError::PackDisabled(pack_id) => write!(f, "Word pack {} is turned off", pack_id),
+            GameError::NoCategorySelected => write!(f, "No category selected for this round"),
+            GameError::Entropy(e) => write!(f, "{}", e),
+            GameError::MatchOver => write!(f, "The match is over - start a new game"),
+            GameError::NoTimer => write!(f, "There's no countdown running"),
+            GameError::TimeNotUp => write!(f, "There's still time left"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl GameAction {
+    fn name(&self) -> &'static str {
+        match self {
+            GameAction::SetPlayerCount(_) => "set the player count",
+            GameAction::SetPlayerName { .. } => "rename a player",
+            GameAction::SetPlayerId { .. } => "link a player",
+            GameAction::MovePlayer { .. } => "move a player",
+            GameAction::UseRoster(_) => "use a saved group",
+            GameAction::SetPlayerCap(_) => "set the player cap",
+            GameAction::SetSeed(_) => "set the seed",
+            GameAction::SetImposterCount(_) => "set the number of imposters",
+            GameAction::SetMrWhiteCount(_) => "set the number of Mr. Whites",
+            GameAction::SetScoring(_) => "change the scoring",
+            GameAction::SetTimers(_) => "change the timers",
+            GameAction::StartGame => "start the game",
+            GameAction::PickCategory { .. } => "pick a category",
+            GameAction::SetPackEnabled { .. } => "toggle a word pack",
+            GameAction::BackToCategories => "go back to categories",
+            GameAction::SetHardMode(_) => "toggle hard mode",
+            GameAction::SetFairDeal(_) => "toggle fair deal",
+            GameAction::SetMaxImposterStreak(_) => "set the imposter streak cap",
+            GameAction::SetLastChance(_) => "toggle the last chance rule",
+            GameAction::StartRound => "start the round",
+            GameAction::BackToCategoryReveal => "go back to the category",
+            GameAction::RevealCard => "reveal a card",
+            GameAction::ProceedToVoting => "start voting",
+            GameAction::SetBallotMode(_) => "toggle secret ballots",
+            GameAction::SetTieBreak(_) => "set the tie-break",
+            GameAction::Evict { .. } => "evict a player",
+            GameAction::OpenBallot => "start the vote",
+            GameAction::CastVote { .. } => "cast a vote",
+            GameAction::CancelBallot => "cancel the vote",
+            GameAction::ConfirmElimination => "confirm the elimination",
+            GameAction::GuessWord { .. } => "guess the word",
+            GameAction::RestartRound => "restart the round",
+            GameAction::ViewScores => "view scores",
+            GameAction::ViewHistory => "view the history",
+            GameAction::NextRound => "start the next round",
+            GameAction::NewGame => "start a new game",
+            GameAction::AddPlayer { .. } => "add a player",
+            GameAction::RemovePlayer { .. } => "remove a player",
+            GameAction::RunTimer { .. } => "start the timer",
+            GameAction::PauseTimer { .. } => "pause the timer",
+            GameAction::TimeUp { .. } => "end the countdown",
+        }
+    }
+}
+
+impl GameScreen {
+    fn name(&self) -> &'static str {
+        match self {
+            GameScreen::Setup => "setup",
+            GameScreen::CategorySelection => "category selection",
+            GameScreen::CategoryReveal { .. } => "category reveal",
+            GameScreen::CardView { .. } => "card view",
+            GameScreen::Voting => "voting",
+            GameScreen::Ballot => "ballot",
+            GameScreen::Elimination { .. } => "elimination",
+            GameScreen::MrWhiteGuess { .. } => "Mr. White's guess",
+            GameScreen::ImposterGuess { .. } => "imposter's last chance",
+            GameScreen::RoundEnd { .. } => "round end",
+            GameScreen::GameScore => "score",
+            GameScreen::History => "history",
+        }
+    }
+}
+
+/// Pure state machine over `GameState`.
+///
+/// Frontends and the server feed it `GameAction`s and render whatever state comes back.
+#[derive(Clone, Debug)]
+pub struct GameEngine {
+    state: GameState,
+}
+
+impl GameEngine {
+    pub fn new(state: GameState) -> Self {
+        GameEngine { state }
+    }
+
+    #[cfg(test)]
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    #[cfg(test)]
+    pub fn into_state(self) -> GameState {
+        self.state
+    }
+
+    /// Apply an action and return the resulting state, leaving this engine untouched
+    pub fn apply(&self, action: GameAction) -> Result<GameState, GameError> {
+        let mut next = self.clone();
+        next.dispatch(action)?;
+        Ok(next.state)
+    }
+
+    /// Apply an action in place. On error the state is left unchanged.
+    pub fn dispatch(&mut self, action: GameAction) -> Result<(), GameError> {
+        let mut state = self.state.clone();
+        reduce(&mut state, action)?;
+        self.state = state;
+        Ok(())
+    }
+}
+
+/// Most imposters a round can start with: they must be outnumbered by civilians
+pub fn max_imposters(player_count: usize) -> usize {
+    (player_count.saturating_sub(1) / 2).max(1)
+}
+
+/// Imposters dealt each round: the host's choice, or one for every four players.
+/// Zero is only playable with Mr. White standing in for the imposter.
+pub fn imposter_count_for(player_count: usize, requested: Option<usize>) -> usize {
+    match requested {
+        Some(count) => count.min(max_imposters(player_count)),
+        None => (player_count / 4).clamp(1, max_imposters(player_count)),
+    }
+}
+
+/// Secret roles dealt each round. Mr. Whites are seated first and imposters fill
+/// whatever room is left, so civilians always outnumber the two together.
+pub fn role_counts_for(player_count: usize, imposter_count: Option<usize>, mr_white_count: usize) -> RoleCounts {
+    let most = max_imposters(player_count);
+    let mr_whites = mr_white_count.min(most);
+    RoleCounts {
+        imposters: imposter_count_for(player_count, imposter_count).min(most - mr_whites),
+        mr_whites,
+    }
+}
+
+/// Players who have won the match by reaching the target score (several if they tie for the lead)
+pub fn match_winners(state: &GameState) -> Vec<usize> {
+    let Some(target) = state.scoring.target_score else {
+        return Vec::new();
+    };
+    let best = state.players.iter().map(|p| p.score).max().unwrap_or(0);
+    if best < target {
+        return Vec::new();
+    }
+    (0..state.players.len()).filter(|&i| state.players[i].score == best).collect()
+}
+
+/// Number of players implied by the setup screen input, up to the game's player cap
+pub fn requested_player_count(input: &str, cap: usize) -> usize {
+    input.parse::<usize>().unwrap_or(MIN_PLAYERS).clamp(MIN_PLAYERS, cap.clamp(MIN_PLAYERS, MAX_PLAYERS))
+}
+
+/// Slots whose name, trimmed and ignoring case, was already used by an earlier slot
+pub fn duplicate_names(names: &[String]) -> BTreeSet<usize> {
+    let mut seen = HashSet::new();
+    names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| {
+            let name = name.trim().to_lowercase();
+            !name.is_empty() && !seen.insert(name)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn invalid(action: &GameAction, screen: &GameScreen) -> GameError {
+    GameError::InvalidAction { action: action.name(), screen: screen.name() }
+}
+
+fn reduce(state: &mut GameState, action: GameAction) -> Result<(), GameError> {
+    let (screen, revote) = (state.game_screen.clone(), state.revote_candidates.clone());
+    transition(state, action)?;
+    // A new phase (or a fresh revote) gets a fresh countdown
+    if state.game_screen != screen || state.revote_candidates != revote {
+        arm_timer(state);
+    }
+    Ok(())
+}
+
+fn transition(state: &mut GameState, action: GameAction) -> Result<(), GameError> {
+    let screen = state.game_screen.clone();
+    match (&screen, action) {
+        (GameScreen::Setup, GameAction::SetPlayerCount(input)) => {
+            let count = requested_player_count(&input, state.player_cap);
+            state.player_count_input = input;
+            state.player_names.resize(count, String::new());
+            state.player_ids.resize(count, String::new());
+        }
+        (GameScreen::Setup, GameAction::SetPlayerName { index, name }) => {
+            let count = requested_player_count(&state.player_count_input, state.player_cap);
+            if index >= count {
+                return Err(GameError::UnknownPlayer(index));
+            }
+            if state.player_names.len() < count {
+                state.player_names.resize(count, String::new());
+            }
+            state.player_ids.resize(state.player_names.len(), String::new());
+            // A different name is a different player until it's linked again
+            if state.player_names[index] != name {
+                state.player_ids[index].clear();
+            }
+            state.player_names[index] = name;
+        }
+        (GameScreen::Setup, GameAction::SetPlayerId { index, id }) => {
+            if index >= state.player_names.len() {
+                return Err(GameError::UnknownPlayer(index));
+            }
+            state.player_ids.resize(state.player_names.len(), String::new());
+            state.player_ids[index] = id.unwrap_or_default();
+        }
+        (GameScreen::Setup, GameAction::MovePlayer { from, to }) => {
+            let count = state.player_names.len();
+            if let Some(&bad) = [from, to].iter().find(|&&i| i >= count) {
+                return Err(GameError::UnknownPlayer(bad));
+            }
+            state.player_ids.resize(count, String::new());
+            let name = state.player_names.remove(from);
+            state.player_names.insert(to, name);
+            let id = state.player_ids.remove(from);
+            state.player_ids.insert(to, id);
+        }
+        (GameScreen::Setup, GameAction::UseRoster(players)) => {
+            if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players.len()) {
+                return Err(GameError::InvalidPlayerCount(players.len()));
+            }
+            state.player_cap = state.player_cap.max(players.len());
+            state.player_count_input = players.len().to_string();
+            (state.player_names, state.player_ids) = players.into_iter().map(|p| (p.name, p.id)).unzip();
+        }
+        (GameScreen::Setup, GameAction::SetPlayerCap(cap)) => {
+            state.player_cap = cap.clamp(MIN_PLAYERS, MAX_PLAYERS);
+            let count = requested_player_count(&state.player_count_input, state.player_cap);
+            state.player_names.resize(count, String::new());
+            state.player_ids.resize(count, String::new());
+        }
+        (GameScreen::Setup, GameAction::SetSeed(seed)) => {
+            state.seed = seed;
+        }
+        (GameScreen::Setup, GameAction::SetImposterCount(count)) => {
+            state.imposter_count = count;
+        }
+        (GameScreen::Setup, GameAction::SetMrWhiteCount(count)) => {
+            state.mr_white_count = count;
+        }
+        (GameScreen::Setup, GameAction::SetScoring(rules)) => {
+            state.scoring = rules;
+        }
+        (GameScreen::Setup, GameAction::SetTimers(timers)) => {
+            state.timers = timers;
+        }
+        (GameScreen::Setup, GameAction::StartGame) => {
+            let count = requested_player_count(&state.player_count_input, state.player_cap);
+            if state.player_names.len() != count {
+                return Err(GameError::InvalidPlayerCount(state.player_names.len()));
+            }
+            for name in state.player_names.iter_mut() {
+                *name = name.trim().to_string();
+            }
+            if let Some(blank) = state.player_names.iter().position(|n| n.is_empty()) {
+                return Err(GameError::EmptyPlayerName(blank));
+            }
+            if let Some(&duplicate) = duplicate_names(&state.player_names).first() {
+                return Err(GameError::DuplicatePlayerName(duplicate));
+            }
+            // Picked for a bigger table before players were taken off
+            if let Some(requested) = state.imposter_count.filter(|&n| n > max_imposters(count)) {
+                return Err(GameError::InvalidImposterCount(requested));
+            }
+            let roles = role_counts_for(count, state.imposter_count, state.mr_white_count);
+            if roles.imposters + roles.mr_whites == 0 {
+                return Err(GameError::NoSecretRoles);
+            }
+            assign_player_ids(state);
+            state.players = state
+                .player_names
+                .iter()
+                .zip(&state.player_ids)
+                .map(|(name, id)| Player {
+                    id: id.clone(),
+                    name: name.clone(),
+                    score: 0,
+                    is_eliminated: false,
+                    times_imposter: 0,
+                    imposter_streak: 0,
+                })
+                .collect();
+            state.round_number = 1;
+            state.cards.clear();
+            state.current_round_words = None;
+            // Every game is seeded, so any game can be replayed from the seed on the score screen
+            state.game_seed = match state.seed {
+                Some(seed) => seed,
+                None => entropy_seed().map_err(GameError::Entropy)?,
+            };
+            state.rng_state = state.game_seed;
+            // Randomize starting player for new game
+            let player_count = state.players.len();
+            state.starting_player_index = with_game_rng(state, |rng| get_random_starting_index(rng, player_count));
+            state.history.push(GameEvent::GameStarted {
+                players: state.player_names.clone(),
+                player_ids: state.player_ids.clone(),
+                seed: state.game_seed,
+            });
+            state.game_screen = GameScreen::CategorySelection;
+        }
+        (GameScreen::CategorySelection, GameAction::PickCategory { category_id }) => {
+            let category = find_category(&category_id).ok_or(GameError::UnknownCategory(category_id))?;
+            if state.disabled_packs.contains(&category.pack_id) {
+                return Err(GameError::PackDisabled(category.pack_id));
+            }
+            state.selected_category_id = Some(category.id);
+            // A different category means a different deal
+            state.cards.clear();
+            state.game_screen = GameScreen::CategoryReveal {
+                category_name: category.name,
+                category_icon: category.icon,
+            };
+        }
+        (GameScreen::CategorySelection, GameAction::SetPackEnabled { pack_id, enabled }) => {
+            state.disabled_packs.retain(|p| *p != pack_id);
+            if !enabled {
+                state.disabled_packs.push(pack_id);
+            }
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::BackToCategories) => {
+            state.game_screen = GameScreen::CategorySelection;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::SetHardMode(enabled)) => {
+            state.hide_imposter_identity = enabled;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::SetFairDeal(enabled)) => {
+            state.fair_deal = enabled;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::SetMaxImposterStreak(cap)) => {
+            state.max_imposter_streak = cap;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::SetLastChance(enabled)) => {
+            state.imposter_last_chance = enabled;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::SetBallotMode(enabled)) => {
+            state.ballot_mode = enabled;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::SetTieBreak(tie_break)) => {
+            state.tie_break = tie_break;
+        }
+        (GameScreen::CategoryReveal { .. }, GameAction::StartRound) => {
+            if state.cards.is_empty() {
+                deal_cards(state)?;
+            }
+            state.game_screen = GameScreen::CardView { current_player_index: 0 };
+        }
+        (GameScreen::CardView { current_player_index: 0 }, GameAction::BackToCategoryReveal) => {
+            let (category_name, category_icon) =
+                state.current_category.clone().ok_or(GameError::NoCategorySelected)?;
+            state.game_screen = GameScreen::CategoryReveal { category_name, category_icon };
+        }
+        (GameScreen::CardView { current_player_index }, GameAction::RevealCard) => {
+            if *current_player_index >= state.players.len() {
+                return Err(invalid(&GameAction::RevealCard, &screen));
+            }
+            state.game_screen = GameScreen::CardView { current_player_index: current_player_index + 1 };
+        }
+        (GameScreen::CardView { .. }, GameAction::ProceedToVoting) => {
+            state.game_screen = GameScreen::Voting;
+        }
+        (GameScreen::Voting, GameAction::Evict { player_index }) => {
+            let player = state.players.get(player_index).ok_or(GameError::UnknownPlayer(player_index))?;
+            if player.is_eliminated {
+                return Err(GameError::PlayerAlreadyEliminated(player_index));
+            }
+            show_elimination(state, player_index);
+        }
+        (GameScreen::Voting, GameAction::OpenBallot) => {
+            open_ballot(state);
+        }
+        (GameScreen::Ballot, GameAction::CastVote { voter, target }) => {
+            cast_vote(state, voter, target)?;
+        }
+        (GameScreen::Ballot, GameAction::CancelBallot) => {
+            state.ballots.clear();
+            state.revote_candidates.clear();
+            state.game_screen = GameScreen::Voting;
+        }
+        (GameScreen::Voting | GameScreen::Ballot, GameAction::RestartRound) => {
+            for player in state.players.iter_mut() {
+                player.is_eliminated = false;
+            }
+            state.ballots.clear();
+            state.revote_candidates.clear();
+            state.cards.clear();
+            undo_imposter_counts(state);
+            state.history.push(GameEvent::RoundAbandoned);
+            state.game_screen = GameScreen::CategorySelection;
+        }
+        (GameScreen::Elimination { eliminated_index, was_imposter, was_mr_white }, GameAction::ConfirmElimination) => {
+            confirm_elimination(state, *eliminated_index, *was_imposter, *was_mr_white)?;
+        }
+        (GameScreen::MrWhiteGuess { player_index }, GameAction::GuessWord { guess }) => {
+            // A close guess is the word with a typo or two (see `match_guess`), so it wins too
+            if record_guess(state, *player_index, guess) != GuessMatch::Miss {
+                // Mr. White worked it out - the win is theirs alone
+                state.players[*player_index].score += state.scoring.mr_white_guess;
+                end_round(state, false, Some(*player_index));
+            } else {
+                settle_round(state);
+            }
+        }
+        (GameScreen::ImposterGuess { player_index }, GameAction::GuessWord { guess }) => {
+            // The civilians still found them, but a good guess is worth something
+            state.players[*player_index].score += match record_guess(state, *player_index, guess) {
+                GuessMatch::Exact => state.scoring.last_chance,
+                GuessMatch::Close => state.scoring.last_chance_close,
+                GuessMatch::Miss => 0,
+            };
+            settle_round(state);
+        }
+        (GameScreen::RoundEnd { .. } | GameScreen::History, GameAction::ViewScores) => {
+            state.game_screen = GameScreen::GameScore;
+        }
+        (GameScreen::GameScore, GameAction::ViewHistory) => {
+            state.game_screen = GameScreen::History;
+        }
+        (GameScreen::RoundEnd { .. } | GameScreen::GameScore, GameAction::NextRound) => {
+            if !match_winners(state).is_empty() {
+                return Err(GameError::MatchOver);
+            }
+            // Reset all player states for new round
+            for player in state.players.iter_mut() {
+                player.is_eliminated = false;
+            }
+            state.cards.clear();
+            state.round_number += 1;
+            // Rotate starting player for next round
+            if !state.players.is_empty() {
+                state.starting_player_index = (state.starting_player_index + 1) % state.players.len();
+            }
+            state.game_screen = GameScreen::CategorySelection;
+        }
+        (GameScreen::RoundEnd { .. } | GameScreen::GameScore, GameAction::NewGame) => {
+            // Clear all game state for a fresh start
+            state.cards.clear();
+            state.imposter_indices.clear();
+            state.mr_white_indices.clear();
+            state.guesses.clear();
+            state.round_number = 1;
+            // The game is over, so its seed can settle any argument
+            state.last_game_seed = Some(state.game_seed);
+            let player_count = state.players.len();
+            state.starting_player_index = with_game_rng(state, |rng| get_random_starting_index(rng, player_count));
+            state.game_screen = GameScreen::Setup;
+        }
+        (GameScreen::RoundEnd { .. } | GameScreen::GameScore, GameAction::AddPlayer { name, id, score }) => {
+            let count = state.players.len();
+            if count >= MAX_PLAYERS {
+                return Err(GameError::InvalidPlayerCount(count + 1));
+            }
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(GameError::EmptyPlayerName(count));
+            }
+            if state.players.iter().any(|p| p.name.trim().to_lowercase() == name.to_lowercase()) {
+                return Err(GameError::DuplicatePlayerName(count));
+            }
+            let id = id
+                .filter(|id| !id.is_empty() && state.players.iter().all(|p| p.id != *id))
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            let score = starting_score(state, score);
+            state.players.push(Player {
+                id: id.clone(),
+                name: name.clone(),
+                score,
+                is_eliminated: false,
+                // Level with whoever has been the imposter least, so fair deal doesn't pile onto them
+                times_imposter: state.players.iter().map(|p| p.times_imposter).min().unwrap_or(0),
+                imposter_streak: 0,
+            });
+            state.history.push(GameEvent::PlayerJoined { name, id, score });
+            seating_changed(state);
+        }
+        (GameScreen::RoundEnd { .. } | GameScreen::GameScore, GameAction::RemovePlayer { player_index }) => {
+            let count = state.players.len();
+            if player_index >= count {
+                return Err(GameError::UnknownPlayer(player_index));
+            }
+            if count <= MIN_PLAYERS {
+                return Err(GameError::InvalidPlayerCount(count - 1));
+            }
+            // Whoever was due to start the next round still does, even if they move up a seat
+            let mut next = (state.starting_player_index + 1) % count;
+            state.players.remove(player_index);
+            if next > player_index {
+                next -= 1;
+            }
+            let count = count - 1;
+            state.starting_player_index = (next % count + count - 1) % count;
+            state.history.push(GameEvent::PlayerLeft { player_index });
+            seating_changed(state);
+        }
+        (_, GameAction::RunTimer { now_ms }) => {
+            let timer = state.timer.as_mut().filter(|t| t.remaining_ms > 0).ok_or(GameError::NoTimer)?;
+            if timer.deadline_ms.is_none() {
+                timer.deadline_ms = Some(now_ms + timer.remaining_ms);
+                timer.paused = false;
+            }
+        }
+        (_, GameAction::PauseTimer { now_ms }) => {
+            let timer = state.timer.as_mut().ok_or(GameError::NoTimer)?;
+            timer.remaining_ms = timer.remaining_at(now_ms);
+            timer.deadline_ms = None;
+            timer.paused = true;
+        }
+        (_, GameAction::TimeUp { now_ms }) => {
+            time_up(state, &screen, now_ms)?;
+        }
+        (_, action) => return Err(invalid(&action, &screen)),
+    }
+    Ok(())
+}
+
+/// Countdown for whatever phase the game has just moved into, if the host timed it
+fn arm_timer(state: &mut GameState) {
+    let timers = &state.timers;
+    let phase = match state.game_screen {
+        GameScreen::CardView { current_player_index } if current_player_index < state.players.len() => {
+            Some((TimedPhase::CardReveal, timers.card_secs))
+        }
+        GameScreen::Voting => Some((TimedPhase::Discussion, timers.discussion_secs)),
+        GameScreen::Ballot => Some((TimedPhase::Voting, timers.voting_secs)),
+        _ => None,
+    };
+    state.timer = phase.filter(|&(_, secs)| secs > 0).map(|(phase, secs)| PhaseTimer::new(phase, secs));
+}
+
+/// Move on once the countdown is over: pass the device on, call the vote, or count it
+fn time_up(state: &mut GameState, screen: &GameScreen, now_ms: i64) -> Result<(), GameError> {
+    let timer = state.timer.as_mut().ok_or(GameError::NoTimer)?;
+    match timer.deadline_ms {
+        Some(deadline) if now_ms >= deadline => {}
+        _ => return Err(GameError::TimeNotUp),
+    }
+    match screen {
+        GameScreen::CardView { current_player_index } if current_player_index + 1 < state.players.len() => {
+            state.game_screen = GameScreen::CardView { current_player_index: current_player_index + 1 };
+        }
+        GameScreen::CardView { .. } => {
+            state.game_screen = GameScreen::Voting;
+        }
+        GameScreen::Voting if state.ballot_mode => {
+            open_ballot(state);
+        }
+        GameScreen::Voting => {
+            // The group still has to agree on who goes, so just stop the clock at zero
+            timer.remaining_ms = 0;
+            timer.deadline_ms = None;
+            timer.paused = true;
+        }
+        GameScreen::Ballot => {
+            // Anyone who hasn't voted yet abstains
+            count_votes(state);
+        }
+        _ => return Err(GameError::NoTimer),
+    }
+    Ok(())
+}
+
+/// Points a latecomer starts on
+pub fn starting_score(state: &GameState, score: StartingScore) -> i32 {
+    match score {
+        StartingScore::Zero => 0,
+        StartingScore::Average if state.players.is_empty() => 0,
+        StartingScore::Average => {
+            let total: i32 = state.players.iter().map(|p| p.score).sum();
+            (f64::from(total) / state.players.len() as f64).round() as i32
+        }
+    }
+}
+
+/// Players were added or removed between rounds. The setup slots follow the table so a new
+/// game starts with the same people, and the finished round's seat numbers no longer line up,
+/// so its result gives way to the scoreboard.
+fn seating_changed(state: &mut GameState) {
+    state.player_names = state.players.iter().map(|p| p.name.clone()).collect();
+    state.player_ids = state.players.iter().map(|p| p.id.clone()).collect();
+    state.player_count_input = state.players.len().to_string();
+    state.player_cap = state.player_cap.max(state.players.len());
+    state.cards.clear();
+    state.imposter_indices.clear();
+    state.mr_white_indices.clear();
+    state.guesses.clear();
+    state.vote_tallies.clear();
+    state.ballots.clear();
+    state.revote_candidates.clear();
+    state.game_screen = GameScreen::GameScore;
+}
+
+/// Give every setup slot a stable ID: the one it was linked to, or a new one.
+/// Two slots can't share a player, so a repeated ID is replaced as well.
+fn assign_player_ids(state: &mut GameState) {
+    state.player_ids.resize(state.player_names.len(), String::new());
+    let mut seen = HashSet::new();
+    for id in state.player_ids.iter_mut() {
+        if id.is_empty() || !seen.insert(id.clone()) {
+            *id = Uuid::new_v4().to_string();
+            seen.insert(id.clone());
+        }
+    }
+}
+
+/// Draw from the game's seeded stream and remember where it got to
+fn with_game_rng<T>(state: &mut GameState, draw: impl FnOnce(&mut SeededRng) -> T) -> T {
+    let mut rng = SeededRng::new(state.rng_state);
+    let result = draw(&mut rng);
+    state.rng_state = rng.state();
+    result
+}
+
+/// Fair deal odds for each player, or `None` for a plain uniform pick.
+///
+/// A player's weight grows with the square of how far they are behind whoever has been the
+/// imposter most, so the rotation evens out quickly without becoming predictable.
+/// Anyone who has hit the streak cap sits the next deal out.
+fn imposter_weights(state: &GameState) -> Option<Vec<u64>> {
+    if !state.fair_deal {
+        return None;
+    }
+    let most = state.players.iter().map(|p| p.times_imposter).max().unwrap_or(0);
+    let cap = state.max_imposter_streak;
+    Some(
+        state
+            .players
+            .iter()
+            .map(|player| {
+                if cap > 0 && player.imposter_streak >= cap {
+                    0
+                } else {
+                    let behind = u64::from(most - player.times_imposter) + 1;
+                    behind * behind
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Deal a fresh set of cards for the selected category
+fn deal_cards(state: &mut GameState) -> Result<(), GameError> {
+    let category_id = state.selected_category_id.clone().ok_or(GameError::NoCategorySelected)?;
+    let category = find_category(&category_id).ok_or(GameError::UnknownCategory(category_id))?;
+    let player_count = state.players.len();
+    if player_count < MIN_PLAYERS {
+        return Err(GameError::InvalidPlayerCount(player_count));
+    }
+
+    let weights = imposter_weights(state);
+    let used = state.used_pairs.get(&category.id).cloned().unwrap_or_default();
+    let mut orientations = state.pair_orientation.remove(&category.id).unwrap_or_default();
+    let roles = role_counts_for(player_count, state.imposter_count, state.mr_white_count);
+    let DealtCards { cards, imposter_indices, mr_white_indices, pair_id } = with_game_rng(state, |rng| {
+        generate_cards_for_category(
+            rng,
+            &mut orientations,
+            player_count,
+            roles,
+            weights.as_deref(),
+            &used,
+            &category,
+        )
+    });
+    state.pair_orientation.insert(category.id.clone(), orientations);
+
+    // Remember the pair for the rest of the session; start over once the category is used up
+    let used = state.used_pairs.entry(category.id.clone()).or_default();
+    if remaining_pair_count(&category, used) == 0 {
+        used.clear();
+    }
+    used.push(pair_id);
+
+    // Store the words for this round (civilian word, imposter word)
+    let civilian_word = cards
+        .iter()
+        .find(|c| c.card_type == CardType::Normal)
+        .map(|c| c.word.clone())
+        .unwrap_or_default();
+    let imposter_word = cards
+        .iter()
+        .find(|c| c.card_type == CardType::Imposter)
+        .map(|c| c.word.clone())
+        .unwrap_or_default();
+
+    state.guesses.clear();
+    state.vote_tallies.clear();
+    state.cards = cards;
+    for (i, player) in state.players.iter_mut().enumerate() {
+        if imposter_indices.contains(&i) || mr_white_indices.contains(&i) {
+            player.times_imposter += 1;
+            player.imposter_streak += 1;
+        } else {
+            player.imposter_streak = 0;
+        }
+    }
+    state.history.push(GameEvent::RoundStarted {
+        category_id: category.id.clone(),
+        category_name: category.name.clone(),
+        category_icon: category.icon.clone(),
+        civilian_word: civilian_word.clone(),
+        imposter_word: imposter_word.clone(),
+        imposters: imposter_indices.clone(),
+        mr_whites: mr_white_indices.clone(),
+        scores: state.players.iter().map(|p| p.score).collect(),
+    });
+    state.current_round_words = Some((civilian_word, imposter_word));
+    state.imposter_indices = imposter_indices.into_iter().collect();
+    state.mr_white_indices = mr_white_indices.into_iter().collect();
+    state.current_category = Some((category.name, category.icon));
+    Ok(())
+}
+
+/// Reveal who was evicted and what they were
+/// Take back what the last deal added to each player's fair deal counts. The streaks it broke
+/// are counted back up from the rounds before it that weren't abandoned.
+fn undo_imposter_counts(state: &mut GameState) {
+    let Some(deal) = state.history.iter().rposition(|e| matches!(e, GameEvent::RoundStarted { .. })) else {
+        return;
+    };
+    let game = state.history[..deal].iter().filter(|e| matches!(e, GameEvent::GameStarted { .. })).count();
+    let earlier: Vec<PlayedRound> = played_rounds(&state.history[..deal])
+        .into_iter()
+        .filter(|r| r.game == game && !r.events.contains(&GameEvent::RoundAbandoned))
+        .collect();
+    let dealt_secret = |round: &PlayedRound, name: &str| match &round.events[0] {
+        GameEvent::RoundStarted { imposters, mr_whites, .. } => {
+            imposters.iter().chain(mr_whites).any(|&i| round.players.get(i).is_some_and(|n| n == name))
+        }
+        _ => false,
+    };
+    for (i, player) in state.players.iter_mut().enumerate() {
+        if state.imposter_indices.contains(&i) || state.mr_white_indices.contains(&i) {
+            player.times_imposter = player.times_imposter.saturating_sub(1);
+            player.imposter_streak = player.imposter_streak.saturating_sub(1);
+        } else {
+            player.imposter_streak = earlier.iter().rev().take_while(|r| dealt_secret(r, &player.name)).count() as u32;
+        }
+    }
+}
+
+fn show_elimination(state: &mut GameState, player_index: usize) {
+    state.game_screen = GameScreen::Elimination {
+        eliminated_index: player_index,
+        was_imposter: state.imposter_indices.contains(&player_index),
+        was_mr_white: state.mr_white_indices.contains(&player_index),
+    };
+}
+
+/// Start a secret vote with nobody having voted yet
+fn open_ballot(state: &mut GameState) {
+    state.ballots.clear();
+    state.revote_candidates.clear();
+    state.game_screen = GameScreen::Ballot;
+}
+
+/// Players who can be voted for in the current ballot
+pub fn vote_candidates(state: &GameState) -> Vec<usize> {
+    (0..state.players.len())
+        .filter(|&i| !state.players[i].is_eliminated)
+        .filter(|i| state.revote_candidates.is_empty() || state.revote_candidates.contains(i))
+        .collect()
+}
+
+/// Record one secret vote, and count them once everyone still in has voted
+fn cast_vote(state: &mut GameState, voter: usize, target: usize) -> Result<(), GameError> {
+    let player = state.players.get(voter).ok_or(GameError::UnknownPlayer(voter))?;
+    if player.is_eliminated {
+        return Err(GameError::PlayerAlreadyEliminated(voter));
+    }
+    if state.ballots.contains_key(&voter) {
+        return Err(GameError::AlreadyVoted(voter));
+    }
+    if target == voter || !vote_candidates(state).contains(&target) {
+        return Err(GameError::NotACandidate(target));
+    }
+    state.ballots.insert(voter, Some(target));
+
+    let voters = state.players.iter().filter(|p| !p.is_eliminated).count();
+    if state.ballots.len() == voters {
+        count_votes(state);
+    }
+    Ok(())
+}
+
+/// Count a finished ballot and apply the tie-break if the top spot is shared
+fn count_votes(state: &mut GameState) {
+    let ballots: BTreeMap<usize, usize> = state
+        .ballots
+        .iter()
+        .filter_map(|(&voter, &target)| Some((voter, target?)))
+        .collect();
+    let mut counts = BTreeMap::new();
+    for &target in ballots.values() {
+        *counts.entry(target).or_insert(0usize) += 1;
+    }
+    let most = counts.values().copied().max().unwrap_or(0);
+    let tied: Vec<usize> = counts.iter().filter(|(_, &n)| n == most).map(|(&i, _)| i).collect();
+    let was_revote = !state.revote_candidates.is_empty();
+
+    let outcome = match (tied.as_slice(), state.tie_break) {
+        // Nobody voted before time ran out
+        ([], _) => VoteOutcome::NoEviction { tied },
+        ([only], _) => VoteOutcome::Evicted(*only),
+        (_, TieBreak::Revote) if !was_revote => VoteOutcome::Revote { tied },
+        (_, TieBreak::Random) => {
+            let pick = with_game_rng(state, |rng| uniform_index(rng, tied.len()));
+            VoteOutcome::RandomEviction { evicted: tied[pick], tied }
+        }
+        // No eviction, or a revote that tied again
+        _ => VoteOutcome::NoEviction { tied },
+    };
+
+    let tally = VoteTally { round_number: state.round_number, ballots, outcome: outcome.clone() };
+    state.history.push(GameEvent::VoteCounted(tally.clone()));
+    state.vote_tallies.push(tally);
+    state.ballots.clear();
+    state.revote_candidates.clear();
+    match outcome {
+        VoteOutcome::Evicted(evicted) | VoteOutcome::RandomEviction { evicted, .. } => {
+            show_elimination(state, evicted);
+        }
+        VoteOutcome::Revote { tied } => {
+            state.revote_candidates = tied;
+        }
+        VoteOutcome::NoEviction { .. } => {
+            state.round_number += 1;
+            state.game_screen = GameScreen::Voting;
+        }
+    }
+}
+
+/// Eliminate the evicted player. An evicted Mr. White, or an imposter under the last chance
+/// rule, gets to guess the civilian word before the round is settled.
+fn confirm_elimination(
+    state: &mut GameState,
+    eliminated_index: usize,
+    was_imposter: bool,
+    was_mr_white: bool,
+) -> Result<(), GameError> {
+    let player = state
+        .players
+        .get_mut(eliminated_index)
+        .ok_or(GameError::UnknownPlayer(eliminated_index))?;
+    player.is_eliminated = true;
+    let card_type = state.cards.get(eliminated_index).map(|c| c.card_type.clone()).unwrap_or(CardType::Normal);
+    state.history.push(GameEvent::Evicted { player_index: eliminated_index, card_type });
+
+    if !was_imposter && !was_mr_white {
+        // The civilians voted out one of their own
+        let penalty = state.scoring.civilian_eviction_penalty;
+        let civilians = secret_roles(state).iter().map(|secret| !secret).collect::<Vec<_>>();
+        award(state, &civilians, false, -penalty);
+    }
+
+    if was_mr_white {
+        state.game_screen = GameScreen::MrWhiteGuess { player_index: eliminated_index };
+    } else if was_imposter && state.imposter_last_chance {
+        state.game_screen = GameScreen::ImposterGuess { player_index: eliminated_index };
+    } else {
+        settle_round(state);
+    }
+    Ok(())
+}
+
+/// Judge a guess at the civilian word and keep it for the round end screen
+fn record_guess(state: &mut GameState, player_index: usize, guess: String) -> GuessMatch {
+    let civilian_word = state.current_round_words.as_ref().map(|(civilian, _)| civilian.clone()).unwrap_or_default();
+    let synonyms = state
+        .selected_category_id
+        .as_deref()
+        .and_then(find_category)
+        .map(|category| synonyms_for(&category, &civilian_word))
+        .unwrap_or_default();
+    let result = if civilian_word.is_empty() {
+        GuessMatch::Miss
+    } else {
+        match_guess(&guess, &civilian_word, &synonyms)
+    };
+    let guess = WordGuess { player_index, guess: guess.trim().to_string(), result };
+    state.history.push(GameEvent::WordGuessed(guess.clone()));
+    state.guesses.push(guess);
+    result
+}
+
+/// Which seats hold an imposter or Mr. White card this round
+fn secret_roles(state: &GameState) -> Vec<bool> {
+    (0..state.players.len())
+        .map(|i| state.imposter_indices.contains(&i) || state.mr_white_indices.contains(&i))
+        .collect()
+}
+
+/// Add `points` to every player flagged in `side`. Evicted players only score if `include_evicted`.
+fn award(state: &mut GameState, side: &[bool], include_evicted: bool, points: i32) {
+    for (player, &on_side) in state.players.iter_mut().zip(side) {
+        if on_side && (include_evicted || !player.is_eliminated) {
+            player.score += points;
+        }
+    }
+}
+
+/// Decide whether the round is over now that someone has been evicted
+fn settle_round(state: &mut GameState) {
+    let secret = secret_roles(state);
+    let civilians: Vec<bool> = secret.iter().map(|s| !s).collect();
+    let remaining = |side: &[bool]| {
+        state.players.iter().zip(side).filter(|(p, &on_side)| on_side && !p.is_eliminated).count()
+    };
+    let remaining_secret = remaining(&secret);
+    let remaining_civilians = remaining(&civilians);
+    let rules = state.scoring.clone();
+
+    // Every vote they live through is worth something to the players hiding
+    award(state, &secret, false, rules.survival_bonus);
+
+    if remaining_secret == 0 {
+        // Every imposter and Mr. White found - evicted civilians only score in team play
+        award(state, &civilians, rules.whole_team_scores, rules.civilian_win);
+        end_round(state, true, None);
+    } else if remaining_secret >= remaining_civilians {
+        // Civilians can't outvote them any more - the survivors win!
+        award(state, &secret, rules.whole_team_scores, rules.imposter_survival);
+        end_round(state, false, None);
+    } else {
+        // Continue to next voting round
+        state.round_number += 1;
+        state.game_screen = GameScreen::Voting;
+    }
+}
+
+/// Show the round result and log how the scores moved since the deal
+fn end_round(state: &mut GameState, imposter_found: bool, stolen_by: Option<usize>) {
+    let scores: Vec<i32> = state.players.iter().map(|p| p.score).collect();
+    let before = state.history.iter().rev().find_map(|event| match event {
+        GameEvent::RoundStarted { scores, .. } => Some(scores.clone()),
+        _ => None,
+    });
+    let score_changes = scores
+        .iter()
+        .enumerate()
+        .map(|(i, score)| score - before.as_ref().and_then(|b| b.get(i)).copied().unwrap_or(0))
+        .collect();
+    state.history.push(GameEvent::RoundEnded { imposter_found, stolen_by, score_changes, scores });
+    state.game_screen = GameScreen::RoundEnd { imposter_found, game_over: true, stolen_by };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::migrations::restore_game_state;
+    use crate::views::game::types::GameCard;
+
+    const NAMES: [&str; 6] = ["Ann", "Bob", "Cat", "Dan", "Eve", "Fay"];
+
+    /// Setup screen with `count` named players and a fixed seed
+    fn seated(count: usize) -> GameEngine {
+        let mut engine = GameEngine::new(GameState::new("test".to_string()));
+        engine.dispatch(GameAction::SetPlayerCount(count.to_string())).unwrap();
+        for (index, name) in NAMES.iter().take(count).enumerate() {
+            engine.dispatch(GameAction::SetPlayerName { index, name: name.to_string() }).unwrap();
+        }
+        engine.dispatch(GameAction::SetSeed(Some(7))).unwrap();
+        engine
+    }
+
+    /// Cards dealt for the first round
+    fn dealt(count: usize) -> GameEngine {
+        let mut engine = seated(count);
+        engine.dispatch(GameAction::StartGame).unwrap();
+        engine.dispatch(GameAction::PickCategory { category_id: "builtin/food-drinks".to_string() }).unwrap();
+        engine.dispatch(GameAction::StartRound).unwrap();
+        engine
+    }
+
+    /// The discussion under way, with the secret roles dealt to known seats
+    fn discussing(count: usize, imposters: &[usize], mr_whites: &[usize]) -> GameEngine {
+        let mut state = dealt(count).into_state();
+        state.imposter_indices = imposters.iter().copied().collect();
+        state.mr_white_indices = mr_whites.iter().copied().collect();
+        state.cards = (0..count)
+            .map(|i| match (imposters.contains(&i), mr_whites.contains(&i)) {
+                (true, _) => GameCard { card_type: CardType::Imposter, word: "Tea".to_string() },
+                (_, true) => GameCard { card_type: CardType::MrWhite, word: String::new() },
+                _ => GameCard { card_type: CardType::Normal, word: "Coffee".to_string() },
+            })
+            .collect();
+        state.current_round_words = Some(("Coffee".to_string(), "Tea".to_string()));
+        state.game_screen = GameScreen::Voting;
+        GameEngine::new(state)
+    }
+
+    fn evict(engine: &mut GameEngine, player_index: usize) {
+        engine.dispatch(GameAction::Evict { player_index }).unwrap();
+        engine.dispatch(GameAction::ConfirmElimination).unwrap();
+    }
+
+    fn scores(engine: &GameEngine) -> Vec<i32> {
+        engine.state().players.iter().map(|p| p.score).collect()
+    }
+
+    #[test]
+    fn dealing_gives_everyone_a_card() {
+        let engine = dealt(4);
+        let state = engine.state();
+        assert_eq!(state.game_screen, GameScreen::CardView { current_player_index: 0 });
+        assert_eq!(state.cards.len(), 4);
+        assert_eq!(state.imposter_indices.len(), 1);
+        let (civilian, imposter) = state.current_round_words.clone().unwrap();
+        assert_ne!(civilian, imposter);
+        for (i, card) in state.cards.iter().enumerate() {
+            if state.imposter_indices.contains(&i) {
+                assert_eq!((&card.card_type, &card.word), (&CardType::Imposter, &imposter));
+            } else {
+                assert_eq!((&card.card_type, &card.word), (&CardType::Normal, &civilian));
+            }
+        }
+        assert!(matches!(state.history.last(), Some(GameEvent::RoundStarted { .. })));
+        // The same seed deals the same cards
+        assert_eq!(dealt(4).state().cards, state.cards);
+    }
+
+    #[test]
+    fn pair_orientation_is_seeded_and_survives_a_save() {
+        let engine = dealt(4);
+        let state = engine.state();
+        assert!(!state.pair_orientation.is_empty());
+        // The same seed deals the pair the same way round
+        assert_eq!(dealt(4).state().pair_orientation, state.pair_orientation);
+        assert_eq!(dealt(4).state().current_round_words, state.current_round_words);
+
+        let restored = restore_game_state(&serde_json::to_string(state).unwrap()).unwrap();
+        assert_eq!(restored.pair_orientation, state.pair_orientation);
+
+        // A restored game goes on dealing exactly as the one it was saved from, through
+        // enough rounds for the pair it was saved with to come round again
+        let pairs = find_category("builtin/food-drinks").unwrap().pairs.len();
+        let play_on = |state: GameState| {
+            let mut engine = GameEngine::new(state);
+            (0..2 * pairs)
+                .map(|_| {
+                    engine.dispatch(GameAction::ProceedToVoting).unwrap();
+                    engine.dispatch(GameAction::RestartRound).unwrap();
+                    engine.dispatch(GameAction::PickCategory { category_id: "builtin/food-drinks".to_string() }).unwrap();
+                    engine.dispatch(GameAction::StartRound).unwrap();
+                    engine.state().current_round_words.clone().unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(play_on(restored), play_on(state.clone()));
+    }
+
+    #[test]
+    fn evicting_the_imposter_ends_the_round() {
+        let mut engine = discussing(4, &[2], &[]);
+        engine.dispatch(GameAction::Evict { player_index: 2 }).unwrap();
+        assert_eq!(
+            engine.state().game_screen,
+            GameScreen::Elimination { eliminated_index: 2, was_imposter: true, was_mr_white: false }
+        );
+        engine.dispatch(GameAction::ConfirmElimination).unwrap();
+        assert_eq!(
+            engine.state().game_screen,
+            GameScreen::RoundEnd { imposter_found: true, game_over: true, stolen_by: None }
+        );
+        assert_eq!(scores(&engine), vec![10, 10, 0, 10]);
+    }
+
+    #[test]
+    fn evicting_a_civilian_carries_on() {
+        let mut engine = discussing(4, &[3], &[]);
+        evict(&mut engine, 0);
+        assert_eq!(engine.state().game_screen, GameScreen::Voting);
+        assert_eq!(engine.state().round_number, 2);
+        assert!(engine.state().players[0].is_eliminated);
+        assert_eq!(scores(&engine), vec![0, 0, 0, 0]);
+        assert_eq!(
+            engine.dispatch(GameAction::Evict { player_index: 0 }),
+            Err(GameError::PlayerAlreadyEliminated(0))
+        );
+    }
+
+    #[test]
+    fn a_tied_vote_goes_to_a_revote() {
+        let mut engine = discussing(4, &[3], &[]);
+        engine.dispatch(GameAction::OpenBallot).unwrap();
+        for (voter, target) in [(0, 1), (1, 0), (2, 1), (3, 0)] {
+            engine.dispatch(GameAction::CastVote { voter, target }).unwrap();
+        }
+        let state = engine.state();
+        assert_eq!(state.game_screen, GameScreen::Ballot);
+        assert_eq!(state.revote_candidates, vec![0, 1]);
+        assert_eq!(state.vote_tallies[0].outcome, VoteOutcome::Revote { tied: vec![0, 1] });
+        assert!(state.ballots.is_empty());
+        assert_eq!(
+            engine.dispatch(GameAction::CastVote { voter: 0, target: 2 }),
+            Err(GameError::NotACandidate(2))
+        );
+    }
+
+    #[test]
+    fn restarting_the_round_and_starting_a_new_game() {
+        let mut engine = discussing(4, &[3], &[]);
+        engine.dispatch(GameAction::RestartRound).unwrap();
+        assert_eq!(engine.state().game_screen, GameScreen::CategorySelection);
+        assert!(engine.state().cards.is_empty());
+        assert_eq!(engine.state().history.last(), Some(&GameEvent::RoundAbandoned));
+
+        let mut engine = discussing(4, &[3], &[]);
+        evict(&mut engine, 3);
+        engine.dispatch(GameAction::NewGame).unwrap();
+        let state = engine.state();
+        assert_eq!(state.game_screen, GameScreen::Setup);
+        assert_eq!(state.round_number, 1);
+        assert!(state.cards.is_empty() && state.imposter_indices.is_empty());
+        assert_eq!(state.player_names, NAMES[..4].to_vec());
+        assert_eq!(state.last_game_seed, Some(7));
+    }
+
+    #[test]
+    fn restarting_the_round_takes_back_its_imposter_counts() {
+        let counts = |engine: &GameEngine| -> Vec<(u32, u32)> {
+            engine.state().players.iter().map(|p| (p.times_imposter, p.imposter_streak)).collect()
+        };
+        let redeal = |engine: &mut GameEngine| {
+            engine.dispatch(GameAction::PickCategory { category_id: "builtin/food-drinks".to_string() }).unwrap();
+            engine.dispatch(GameAction::StartRound).unwrap();
+            engine.dispatch(GameAction::ProceedToVoting).unwrap();
+        };
+
+        let mut engine = dealt(4);
+        engine.dispatch(GameAction::ProceedToVoting).unwrap();
+        let mut streaks_broken = false;
+        for _ in 0..6 {
+            let imposter = *engine.state().imposter_indices.iter().next().unwrap();
+            evict(&mut engine, imposter);
+            engine.dispatch(GameAction::NextRound).unwrap();
+
+            let before = counts(&engine);
+            redeal(&mut engine);
+            streaks_broken |= before.iter().zip(counts(&engine)).any(|(b, after)| b.1 > 0 && after.1 == 0);
+            engine.dispatch(GameAction::RestartRound).unwrap();
+            assert_eq!(counts(&engine), before);
+            redeal(&mut engine);
+        }
+        // The seed deals so that some restarts had broken streaks to put back
+        assert!(streaks_broken);
+    }
+
+    #[test]
+    fn actions_in_the_wrong_phase_are_rejected() {
+        let mut engine = seated(4);
+        assert_eq!(
+            engine.dispatch(GameAction::ProceedToVoting),
+            Err(GameError::InvalidAction { action: "start voting", screen: "setup" })
+        );
+        assert_eq!(engine.state().game_screen, GameScreen::Setup);
+
+        let mut engine = discussing(4, &[3], &[]);
+        let before = format!("{:?}", engine.state());
+        assert!(engine.dispatch(GameAction::StartRound).is_err());
+        assert!(engine.dispatch(GameAction::NewGame).is_err());
+        assert_eq!(format!("{:?}", engine.state()), before);
+    }
+
+    #[test]
+    fn starting_needs_civilians_to_outnumber_the_imposters() {
+        let mut engine = seated(6);
+        engine.dispatch(GameAction::SetImposterCount(Some(2))).unwrap();
+        engine.dispatch(GameAction::SetPlayerCount("4".to_string())).unwrap();
+        assert_eq!(engine.dispatch(GameAction::StartGame), Err(GameError::InvalidImposterCount(2)));
+        assert_eq!(engine.state().game_screen, GameScreen::Setup);
+
+        engine.dispatch(GameAction::SetImposterCount(Some(0))).unwrap();
+        assert_eq!(engine.dispatch(GameAction::StartGame), Err(GameError::NoSecretRoles));
+        engine.dispatch(GameAction::SetImposterCount(Some(1))).unwrap();
+        assert!(engine.dispatch(GameAction::StartGame).is_ok());
+    }
+
+    #[test]
+    fn imposters_win_once_they_match_the_civilians() {
+        let mut engine = discussing(5, &[3, 4], &[]);
+        // One imposter found isn't enough while the other is still hiding
+        evict(&mut engine, 3);
+        assert_eq!(engine.state().game_screen, GameScreen::Voting);
+        evict(&mut engine, 0);
+        assert_eq!(engine.state().game_screen, GameScreen::Voting);
+        // One civilian against one imposter can't outvote them
+        evict(&mut engine, 1);
+        assert_eq!(
+            engine.state().game_screen,
+            GameScreen::RoundEnd { imposter_found: false, game_over: true, stolen_by: None }
+        );
+        assert_eq!(scores(&engine), vec![0, 0, 0, 0, 20]);
+    }
+
+    #[test]
+    fn mr_white_steals_the_win_with_the_civilian_word() {
+        for guess in ["coffee", "Cofee"] {
+            let mut engine = discussing(4, &[], &[1]);
+            evict(&mut engine, 1);
+            assert_eq!(engine.state().game_screen, GameScreen::MrWhiteGuess { player_index: 1 });
+            engine.dispatch(GameAction::GuessWord { guess: guess.to_string() }).unwrap();
+            assert_eq!(
+                engine.state().game_screen,
+                GameScreen::RoundEnd { imposter_found: false, game_over: true, stolen_by: Some(1) }
+            );
+            assert_eq!(scores(&engine), vec![0, 20, 0, 0]);
+        }
+
+        let mut engine = discussing(4, &[], &[1]);
+        evict(&mut engine, 1);
+        engine.dispatch(GameAction::GuessWord { guess: "Tea".to_string() }).unwrap();
+        assert_eq!(
+            engine.state().game_screen,
+            GameScreen::RoundEnd { imposter_found: true, game_over: true, stolen_by: None }
+        );
+        assert_eq!(scores(&engine), vec![10, 0, 10, 10]);
+    }
+
+    #[test]
+    fn an_evicted_imposter_scores_for_a_last_chance_guess() {
+        for (guess, points) in [("Coffee", 10), ("Cofee", 5), ("Milk", 0)] {
+            let mut state = discussing(4, &[2], &[]).into_state();
+            state.imposter_last_chance = true;
+            let mut engine = GameEngine::new(state);
+            evict(&mut engine, 2);
+            assert_eq!(engine.state().game_screen, GameScreen::ImposterGuess { player_index: 2 });
+            engine.dispatch(GameAction::GuessWord { guess: guess.to_string() }).unwrap();
+            // The civilians still win the round
+            assert_eq!(
+                engine.state().game_screen,
+                GameScreen::RoundEnd { imposter_found: true, game_over: true, stolen_by: None }
+            );
+            assert_eq!(scores(&engine), vec![10, 10, points, 10]);
+        }
+    }
+
+    #[test]
+    fn the_match_ends_once_someone_reaches_the_target() {
+        let with_scores = |before: [i32; 4]| {
+            let mut state = discussing(4, &[3], &[]).into_state();
+            state.scoring.target_score = Some(20);
+            for (player, score) in state.players.iter_mut().zip(before) {
+                player.score = score;
+            }
+            let mut engine = GameEngine::new(state);
+            evict(&mut engine, 3);
+            engine
+        };
+
+        let mut engine = with_scores([5, 0, 0, 0]);
+        assert!(match_winners(engine.state()).is_empty());
+        assert!(engine.dispatch(GameAction::NextRound).is_ok());
+
+        // Everyone on the top score at or past the target shares the win
+        let mut engine = with_scores([10, 10, 5, 0]);
+        assert_eq!(scores(&engine), vec![20, 20, 15, 0]);
+        assert_eq!(match_winners(engine.state()), vec![0, 1]);
+        assert_eq!(engine.dispatch(GameAction::NextRound), Err(GameError::MatchOver));
+        assert!(engine.dispatch(GameAction::NewGame).is_ok());
+    }
+
+    #[test]
+    fn tie_breaks() {
+        let tied_vote = |engine: &mut GameEngine| {
+            for (voter, target) in [(0, 1), (1, 0), (2, 1), (3, 0)] {
+                engine.dispatch(GameAction::CastVote { voter, target }).unwrap();
+            }
+        };
+        let ballot = |tie_break: TieBreak| {
+            let mut state = discussing(4, &[3], &[]).into_state();
+            state.tie_break = tie_break;
+            let mut engine = GameEngine::new(state);
+            engine.dispatch(GameAction::OpenBallot).unwrap();
+            engine
+        };
+
+        let mut engine = ballot(TieBreak::NoEviction);
+        tied_vote(&mut engine);
+        assert_eq!(engine.state().game_screen, GameScreen::Voting);
+        assert_eq!(engine.state().round_number, 2);
+        assert_eq!(engine.state().vote_tallies[0].outcome, VoteOutcome::NoEviction { tied: vec![0, 1] });
+
+        let mut engine = ballot(TieBreak::Random);
+        tied_vote(&mut engine);
+        let VoteOutcome::RandomEviction { evicted, tied } = engine.state().vote_tallies[0].outcome.clone() else {
+            panic!("expected a random eviction");
+        };
+        assert_eq!(tied, vec![0, 1]);
+        assert!(matches!(
+            engine.state().game_screen,
+            GameScreen::Elimination { eliminated_index, .. } if eliminated_index == evicted
+        ));
+
+        // A revote that ties again evicts nobody
+        let mut engine = ballot(TieBreak::Revote);
+        tied_vote(&mut engine);
+        tied_vote(&mut engine);
+        assert_eq!(engine.state().game_screen, GameScreen::Voting);
+        assert_eq!(engine.state().vote_tallies[1].outcome, VoteOutcome::NoEviction { tied: vec![0, 1] });
+    }
+
+    #[test]
+    fn running_out_of_time_moves_the_game_on() {
+        let mut engine = seated(4);
+        let timers = TimerSettings { card_secs: 10, discussion_secs: 60, voting_secs: 30, sound: false };
+        engine.dispatch(GameAction::SetTimers(timers)).unwrap();
+        engine.dispatch(GameAction::StartGame).unwrap();
+        engine.dispatch(GameAction::PickCategory { category_id: "builtin/food-drinks".to_string() }).unwrap();
+        engine.dispatch(GameAction::SetBallotMode(true)).unwrap();
+        engine.dispatch(GameAction::StartRound).unwrap();
+
+        // The next card comes up once the first player's look is over
+        engine.dispatch(GameAction::RunTimer { now_ms: 0 }).unwrap();
+        assert_eq!(engine.dispatch(GameAction::TimeUp { now_ms: 9_999 }), Err(GameError::TimeNotUp));
+        engine.dispatch(GameAction::TimeUp { now_ms: 10_000 }).unwrap();
+        assert_eq!(engine.state().game_screen, GameScreen::CardView { current_player_index: 1 });
+        assert_eq!(engine.state().timer.as_ref().map(|t| t.deadline_ms), Some(None));
+
+        // The discussion runs out into the ballot
+        engine.dispatch(GameAction::ProceedToVoting).unwrap();
+        engine.dispatch(GameAction::RunTimer { now_ms: 20_000 }).unwrap();
+        engine.dispatch(GameAction::TimeUp { now_ms: 80_000 }).unwrap();
+        assert_eq!(engine.state().game_screen, GameScreen::Ballot);
+
+        // Anyone who hasn't voted in time abstains
+        engine.dispatch(GameAction::CastVote { voter: 0, target: 1 }).unwrap();
+        engine.dispatch(GameAction::RunTimer { now_ms: 80_000 }).unwrap();
+        engine.dispatch(GameAction::TimeUp { now_ms: 110_000 }).unwrap();
+        assert_eq!(engine.state().vote_tallies[0].outcome, VoteOutcome::Evicted(1));
+        assert!(matches!(engine.state().game_screen, GameScreen::Elimination { eliminated_index: 1, .. }));
+        assert_eq!(engine.dispatch(GameAction::TimeUp { now_ms: 200_000 }), Err(GameError::NoTimer));
+    }
+
+    #[test]
+    fn seats_can_be_moved_and_filled_from_a_saved_group() {
+        let mut engine = seated(4);
+        engine.dispatch(GameAction::MovePlayer { from: 0, to: 2 }).unwrap();
+        assert_eq!(engine.state().player_names, vec!["Bob", "Cat", "Ann", "Dan"]);
+        assert_eq!(engine.dispatch(GameAction::MovePlayer { from: 4, to: 0 }), Err(GameError::UnknownPlayer(4)));
+
+        let group: Vec<RosterPlayer> = (0..12)
+            .map(|i| RosterPlayer { name: format!("Guest {}", i), id: format!("id-{}", i) })
+            .collect();
+        engine.dispatch(GameAction::UseRoster(group)).unwrap();
+        let state = engine.state();
+        assert_eq!(state.player_cap, 12);
+        assert_eq!(state.player_count_input, "12");
+        assert_eq!((state.player_names[11].as_str(), state.player_ids[11].as_str()), ("Guest 11", "id-11"));
+        let too_few = vec![RosterPlayer { name: "Ann".to_string(), id: String::new() }; 2];
+        assert_eq!(engine.dispatch(GameAction::UseRoster(too_few)), Err(GameError::InvalidPlayerCount(2)));
+
+        // Names are trimmed before they're compared
+        let mut engine = seated(3);
+        engine.dispatch(GameAction::SetPlayerName { index: 2, name: " ann ".to_string() }).unwrap();
+        assert_eq!(engine.dispatch(GameAction::StartGame), Err(GameError::DuplicatePlayerName(2)));
+    }
+
+    #[test]
+    fn joining_and_leaving_keeps_the_next_starting_player() {
+        let mut state = discussing(5, &[4], &[]).into_state();
+        state.starting_player_index = 2;
+        let mut engine = GameEngine::new(state);
+        evict(&mut engine, 4);
+        // Dan is due to start the next round
+        engine.dispatch(GameAction::RemovePlayer { player_index: 0 }).unwrap();
+        assert_eq!(engine.state().game_screen, GameScreen::GameScore);
+        assert_eq!(engine.state().player_names, vec!["Bob", "Cat", "Dan", "Eve"]);
+        engine
+            .dispatch(GameAction::AddPlayer { name: "Fay".to_string(), id: None, score: StartingScore::Average })
+            .unwrap();
+        assert_eq!(scores(&engine), vec![10, 10, 10, 0, 8]);
+        assert_eq!(
+            engine.dispatch(GameAction::AddPlayer { name: " fay".to_string(), id: None, score: StartingScore::Zero }),
+            Err(GameError::DuplicatePlayerName(5))
+        );
+
+        engine.dispatch(GameAction::NextRound).unwrap();
+        let state = engine.state();
+        assert_eq!(state.players[state.starting_player_index].name, "Dan");
+        assert!(state.history.contains(&GameEvent::PlayerLeft { player_index: 0 }));
+    }
+}
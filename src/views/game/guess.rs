@@ -0,0 +1,105 @@
+use crate::views::game::types::{GuessMatch, WordCategory};
+
+// ============================================================================
+// Guessing the civilian word - forgiving about typing, strict about meaning
+// ============================================================================
+
+/// How close a typed guess is to `word`.
+///
+/// Case, surrounding whitespace and doubled spaces never matter, and any synonym the
+/// category lists for the word counts as the word itself. A guess that is a typo or two
+/// away from any of those is `Close`.
+pub fn match_guess(guess: &str, word: &str, synonyms: &[String]) -> GuessMatch {
+    let guess = normalize(guess);
+    if guess.is_empty() {
+        return GuessMatch::Miss;
+    }
+    let answers: Vec<String> = std::iter::once(word)
+        .chain(synonyms.iter().map(String::as_str))
+        .map(normalize)
+        .collect();
+    if answers.contains(&guess) {
+        return GuessMatch::Exact;
+    }
+    let close = answers
+        .iter()
+        .any(|answer| edit_distance(&guess, answer) <= typo_allowance(answer));
+    if close {
+        GuessMatch::Close
+    } else {
+        GuessMatch::Miss
+    }
+}
+
+/// Other answers the category accepts for `word`
+pub fn synonyms_for(category: &WordCategory, word: &str) -> Vec<String> {
+    let word = normalize(word);
+    category
+        .synonyms
+        .iter()
+        .filter(|(key, _)| normalize(key) == word)
+        .flat_map(|(_, alternatives)| alternatives.iter().cloned())
+        .collect()
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Typos forgiven for an answer of this length. Short words get none, or "cat" would accept "car".
+fn typo_allowance(answer: &str) -> usize {
+    match answer.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance: single-character insertions, deletions and substitutions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_and_spacing_do_not_matter() {
+        assert_eq!(match_guess("  coffee ", "Coffee", &[]), GuessMatch::Exact);
+        assert_eq!(match_guess("tv   SHOW", "TV Show", &[]), GuessMatch::Exact);
+        assert_eq!(match_guess("   ", "Coffee", &[]), GuessMatch::Miss);
+    }
+
+    #[test]
+    fn typos_are_close_but_short_words_must_be_exact() {
+        assert_eq!(match_guess("cofee", "Coffee", &[]), GuessMatch::Close);
+        assert_eq!(match_guess("helicoptre", "Helicopter", &[]), GuessMatch::Close);
+        assert_eq!(match_guess("car", "Cat", &[]), GuessMatch::Miss);
+        assert_eq!(match_guess("tea", "Coffee", &[]), GuessMatch::Miss);
+    }
+
+    #[test]
+    fn synonyms_count_as_the_word() {
+        let synonyms = vec!["Automobile".to_string()];
+        assert_eq!(match_guess("automobile", "Car", &synonyms), GuessMatch::Exact);
+        assert_eq!(match_guess("automobil", "Car", &synonyms), GuessMatch::Close);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::views::game::engine::match_winners;
+use crate::views::game::types::{GameEvent, GameState};
+
+// ============================================================================
+// Game history - reading back the session's event log
+// ============================================================================
+
+/// One dealt round from the history log, with the player names its indices refer to
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlayedRound {
+    pub game: usize, // Which game of the session, from 1
+    pub round: usize, // Which round of that game, from 1
+    pub players: Vec<String>,
+    pub events: Vec<GameEvent>, // Starting with its `RoundStarted`
+}
+
+/// Split the log into rounds, oldest first
+pub fn played_rounds(history: &[GameEvent]) -> Vec<PlayedRound> {
+    let mut rounds: Vec<PlayedRound> = Vec::new();
+    let (mut game, mut round, mut players) = (0, 0, Vec::new());
+    for event in history {
+        // Later rounds are dealt to the table as it is once people have come and gone
+        match event {
+            GameEvent::PlayerJoined { name, .. } => players.push(name.clone()),
+            GameEvent::PlayerLeft { player_index } if *player_index < players.len() => {
+                players.remove(*player_index);
+            }
+            _ => {}
+        }
+        match event {
+            GameEvent::GameStarted { players: names, .. } => {
+                game += 1;
+                round = 0;
+                players = names.clone();
+            }
+            GameEvent::RoundStarted { .. } => {
+                round += 1;
+                rounds.push(PlayedRound { game, round, players: players.clone(), events: vec![event.clone()] });
+            }
+            // Anything before the first deal of a game has no round to go in
+            _ if round == 0 => {}
+            _ => {
+                if let Some(current) = rounds.last_mut() {
+                    current.events.push(event.clone());
+                }
+            }
+        }
+    }
+    rounds
+}
+
+/// The whole log as pretty-printed JSON, for saving outside the game. The current game's
+/// seed is left out until someone has won the match, as it would give away the deals to come
+pub fn export_history(state: &GameState) -> serde_json::Result<String> {
+    let mut shown = state.clone();
+    if match_winners(state).is_empty() {
+        shown.hide_seed();
+    }
+    serde_json::to_string_pretty(&serde_json::json!({
+        "session_id": shown.session_id,
+        "history": shown.history,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::types::Player;
+
+    fn round_started(category: &str) -> GameEvent {
+        GameEvent::RoundStarted {
+            category_id: category.to_string(),
+            category_name: category.to_string(),
+            category_icon: String::new(),
+            civilian_word: "Coffee".to_string(),
+            imposter_word: "Tea".to_string(),
+            imposters: vec![0],
+            mr_whites: Vec::new(),
+            scores: vec![0, 0, 0],
+        }
+    }
+
+    #[test]
+    fn rounds_are_grouped_by_game() {
+        let names = |n: &str| vec![n.to_string(), "B".to_string(), "C".to_string()];
+        let history = vec![
+            GameEvent::GameStarted { players: names("A"), player_ids: Vec::new(), seed: 1 },
+            round_started("one"),
+            GameEvent::RoundAbandoned,
+            round_started("two"),
+            GameEvent::GameStarted { players: names("Z"), player_ids: Vec::new(), seed: 2 },
+            round_started("three"),
+        ];
+        let rounds = played_rounds(&history);
+        let summary: Vec<(usize, usize, &str, usize)> =
+            rounds.iter().map(|r| (r.game, r.round, r.players[0].as_str(), r.events.len())).collect();
+        assert_eq!(summary, vec![(1, 1, "A", 2), (1, 2, "A", 1), (2, 1, "Z", 1)]);
+    }
+
+    #[test]
+    fn later_rounds_follow_who_joined_and_left() {
+        let history = vec![
+            GameEvent::GameStarted { players: vec!["A".into(), "B".into(), "C".into()], player_ids: Vec::new(), seed: 1 },
+            round_started("one"),
+            GameEvent::PlayerLeft { player_index: 0 },
+            GameEvent::PlayerJoined { name: "D".into(), id: String::new(), score: 0 },
+            round_started("two"),
+        ];
+        let rounds = played_rounds(&history);
+        // The changes are logged after the round they followed, which still has its own players
+        assert_eq!(rounds[0].players, vec!["A", "B", "C"]);
+        assert_eq!(rounds[0].events.len(), 3);
+        assert_eq!(rounds[1].players, vec!["B", "C", "D"]);
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let mut state = GameState::new("abc".to_string());
+        let started = GameEvent::GameStarted { players: vec!["A".to_string()], player_ids: Vec::new(), seed: 7 };
+        state.history = vec![started, round_started("x")];
+        let json: serde_json::Value = serde_json::from_str(&export_history(&state).unwrap()).unwrap();
+        assert_eq!(json["session_id"], "abc");
+        let history: Vec<GameEvent> = serde_json::from_value(json["history"].clone()).unwrap();
+        assert_eq!(history.len(), state.history.len());
+        assert_eq!(history[1..], state.history[1..]);
+    }
+
+    #[test]
+    fn export_keeps_the_seed_hidden_until_the_match_is_won() {
+        let mut state = GameState::new("abc".to_string());
+        state.players = vec![Player {
+            id: String::new(),
+            name: "A".to_string(),
+            score: 0,
+            is_eliminated: false,
+            times_imposter: 0,
+            imposter_streak: 0,
+        }];
+        state.scoring.target_score = Some(20);
+        state.history = vec![GameEvent::GameStarted { players: vec!["A".to_string()], player_ids: Vec::new(), seed: 7 }];
+        let seed_in = |state: &GameState| {
+            let json: serde_json::Value = serde_json::from_str(&export_history(state).unwrap()).unwrap();
+            json["history"][0]["GameStarted"]["seed"].clone()
+        };
+        assert_eq!(seed_in(&state), 0);
+
+        state.players[0].score = 20;
+        assert_eq!(seed_in(&state), 7);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::views::game::rng::EntropyError;
+use crate::views::game::types::{GameState, SCHEMA_VERSION};
+use crate::views::game::utils::find_category_by_name;
+use serde_json::{Map, Value};
+use std::fmt;
+
+// ============================================================================
+// Save migrations - bringing games saved by older versions up to date
+// ============================================================================
+
+/// Rewrites a save from one layout into the next, working on the raw JSON
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` takes a save from version `n` to version `n + 1`.
+///
+/// Saves from before versioning have no `version` and count as 0. They may be in any
+/// layout from that time, so each step only touches the fields it finds. A change to
+/// `GameState` that older saves can't be read into bumps `SCHEMA_VERSION` and adds a step here.
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [categories_by_id, several_imposters];
+
+/// Why a saved game couldn't be picked up again
+#[derive(Clone, PartialEq, Debug)]
+pub enum RestoreError {
+    /// Not a saved game this version knows how to read
+    Unreadable(String),
+    /// Saved by a newer version of the game
+    TooNew(u32),
+    /// Pasted or picked for import, but not a game exported from here
+    NotAnExport,
+    /// An imported game needed a fresh seed and none could be drawn
+    Entropy(EntropyError),
+}
+
+impl fmt::Display for RestoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreError::Unreadable(e) => write!(f, "The save is damaged ({})", e),
+            RestoreError::TooNew(version) => write!(
+                f,
+                "It was saved by a newer version of the game (save format {}, this version reads up to {})",
+                version, SCHEMA_VERSION
+            ),
+            RestoreError::NotAnExport => write!(f, "That isn't a game exported from Ultimate Imposter"),
+            RestoreError::Entropy(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RestoreError {}
+
+/// Read a saved game written by this or any earlier version
+pub fn restore_game_state(json: &str) -> Result<GameState, RestoreError> {
+    let save: Value = serde_json::from_str(json).map_err(|e| RestoreError::Unreadable(e.to_string()))?;
+    restore_game_value(save)
+}
+
+/// Read a saved game that has already been parsed, e.g. one inside an export
+pub fn restore_game_value(mut save: Value) -> Result<GameState, RestoreError> {
+    migrate(&mut save)?;
+    serde_json::from_value(save).map_err(|e| RestoreError::Unreadable(e.to_string()))
+}
+
+/// Bring a save up to the current layout, one version at a time
+pub fn migrate(save: &mut Value) -> Result<(), RestoreError> {
+    let fields = save
+        .as_object_mut()
+        .ok_or_else(|| RestoreError::Unreadable("not a saved game".to_string()))?;
+    let version = match fields.get("version").and_then(Value::as_u64) {
+        Some(version) => u32::try_from(version).unwrap_or(u32::MAX),
+        None => 0,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(RestoreError::TooNew(version));
+    }
+    for step in &MIGRATIONS[version as usize..] {
+        step(fields);
+    }
+    fields.insert("version".to_string(), SCHEMA_VERSION.into());
+    Ok(())
+}
+
+/// 0 -> 1: categories were picked by their position in words.yaml. Positions move when
+/// categories are added, so find the category again by name.
+fn categories_by_id(save: &mut Map<String, Value>) {
+    save.remove("selected_category_index");
+    if save.get("selected_category_id").is_some_and(|id| !id.is_null()) {
+        return;
+    }
+    let revealed = save.get("game_screen").and_then(|screen| screen.pointer("/CategoryReveal/category_name"));
+    let name = revealed
+        .or_else(|| save.get("current_category").and_then(|category| category.get(0)))
+        .and_then(Value::as_str);
+    if let Some(category) = name.and_then(find_category_by_name) {
+        save.insert("selected_category_id".to_string(), category.id.into());
+    }
+}
+
+/// 1 -> 2: a round had one imposter, in `imposter_index`, and fair deal tracked a single
+/// streak for whoever was dealt it last. Streaks now belong to each player.
+fn several_imposters(save: &mut Map<String, Value>) {
+    if let Some(index) = save.remove("imposter_index") {
+        // Only meaningful while cards are dealt; otherwise it was just left at 0
+        let dealt = save.get("cards").and_then(Value::as_array).is_some_and(|cards| !cards.is_empty());
+        let indices = if dealt { vec![index] } else { Vec::new() };
+        save.entry("imposter_indices").or_insert(Value::Array(indices));
+    }
+    let last = save.remove("last_imposter").and_then(|last| last.as_u64());
+    let streak = save.remove("imposter_streak").unwrap_or(Value::Null);
+    let player = last.and_then(|i| save.get_mut("players")?.get_mut(usize::try_from(i).ok()?)?.as_object_mut());
+    if let Some(player) = player {
+        player.entry("imposter_streak").or_insert(streak);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::types::GameScreen;
+    use std::collections::BTreeSet;
+
+    const BASELINE: &str = include_str!("../../../tests/fixtures/saves/baseline.json");
+    const FAIR_DEAL: &str = include_str!("../../../tests/fixtures/saves/fair_deal.json");
+    const UNVERSIONED: &str = include_str!("../../../tests/fixtures/saves/unversioned.json");
+
+    #[test]
+    fn first_release_saves_are_restored() {
+        let state = restore_game_state(BASELINE).unwrap();
+        assert_eq!(state.version, SCHEMA_VERSION);
+        assert_eq!(state.game_screen, GameScreen::Voting);
+        assert_eq!(state.imposter_indices, BTreeSet::from([2]));
+        assert_eq!(state.selected_category_id.as_deref(), Some("builtin/food-drinks"));
+        let scores: Vec<i32> = state.players.iter().map(|p| p.score).collect();
+        assert_eq!(scores, vec![3, 0, 2, 0]);
+    }
+
+    #[test]
+    fn single_streaks_move_onto_the_player() {
+        let state = restore_game_state(FAIR_DEAL).unwrap();
+        assert_eq!(state.imposter_indices, BTreeSet::from([3]));
+        let streaks: Vec<u32> = state.players.iter().map(|p| p.imposter_streak).collect();
+        assert_eq!(streaks, vec![0, 0, 0, 2]);
+        assert_eq!(state.players[3].times_imposter, 3);
+        assert!(state.fair_deal);
+    }
+
+    #[test]
+    fn recent_saves_only_gain_a_version() {
+        let state = restore_game_state(UNVERSIONED).unwrap();
+        assert_eq!(state.history.len(), 5);
+        assert_eq!(state.version, SCHEMA_VERSION);
+        let mut expected: Value = serde_json::from_str(UNVERSIONED).unwrap();
+        expected["version"] = SCHEMA_VERSION.into();
+        // Fields added since without a format change start out empty
+        expected["save_name"] = "".into();
+        expected["last_played_ms"] = 0.into();
+        expected["last_game_seed"] = Value::Null;
+        assert_eq!(serde_json::to_value(&state).unwrap(), expected);
+    }
+
+    #[test]
+    fn current_saves_round_trip() {
+        let state = GameState::new("abc".to_string());
+        let restored = restore_game_state(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(restored).unwrap(), serde_json::to_value(state).unwrap());
+    }
+
+    #[test]
+    fn unusable_saves_are_reported() {
+        let mut newer: Value = serde_json::from_str(UNVERSIONED).unwrap();
+        newer["version"] = (SCHEMA_VERSION + 1).into();
+        assert_eq!(
+            restore_game_state(&newer.to_string()).unwrap_err(),
+            RestoreError::TooNew(SCHEMA_VERSION + 1)
+        );
+        assert!(matches!(restore_game_state("{\"session_id\":"), Err(RestoreError::Unreadable(_))));
+        assert!(matches!(restore_game_state("[]"), Err(RestoreError::Unreadable(_))));
+        assert!(matches!(restore_game_state("{\"session_id\":\"x\"}"), Err(RestoreError::Unreadable(_))));
+    }
+}
@@ -1,4 +1,8 @@
+use crate::views::game::migrations::{restore_game_state, RestoreError};
 use crate::views::game::types::GameState;
+#[cfg(target_arch = "wasm32")]
+use crate::views::game::clock::now_ms;
+use dioxus::prelude::*;
 
 #[cfg(target_arch = "wasm32")]
 use once_cell::sync::Lazy;
@@ -9,6 +13,18 @@ use std::sync::Mutex;
 // Session Management & Persistence Functions
 // ============================================================================
 
+/// Each session's game is saved under this prefix followed by its session ID
+#[cfg(target_arch = "wasm32")]
+const SAVE_KEY_PREFIX: &str = "ultimate_imposter_game_";
+
+/// localStorage key listing the session IDs of the games this browser created or imported
+#[cfg(target_arch = "wasm32")]
+const SAVED_GAMES_KEY: &str = "ultimate_imposter_saved_games";
+
+/// localStorage key for the token this browser's server saves are recorded under
+#[cfg(target_arch = "wasm32")]
+const OWNER_KEY: &str = "ultimate_imposter_owner";
+
 /// Generate a unique session ID
 pub fn generate_session_id() -> String {
     use uuid::Uuid;
@@ -45,8 +61,74 @@ pub fn save_session_id(_session_id: &str) {
     }
 }
 
-/// Load game state from localStorage
-pub fn load_game_state(session_id: &str) -> Option<GameState> {
+/// This browser's token for the saves it writes to the server, made up the first time it's needed.
+/// The server only lets the browser that first saved a game delete it.
+#[cfg(target_arch = "wasm32")]
+fn owner_token() -> String {
+    use web_sys::window;
+
+    let storage = window().and_then(|w| w.local_storage().ok().flatten());
+    if let Some(token) = storage.as_ref().and_then(|s| s.get_item(OWNER_KEY).ok().flatten()) {
+        return token;
+    }
+    let token = generate_session_id();
+    if let Some(storage) = storage {
+        let _ = storage.set_item(OWNER_KEY, &token);
+    }
+    token
+}
+
+/// Session IDs of the games this browser created or imported, oldest first.
+/// Games saved before the list was kept are picked up from their localStorage keys.
+fn saved_game_ids() -> Vec<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) else {
+            return Vec::new();
+        };
+        let mut ids: Vec<String> = storage
+            .get_item(SAVED_GAMES_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for key in (0..storage.length().unwrap_or(0)).filter_map(|i| storage.key(i).ok().flatten()) {
+            if let Some(session_id) = key.strip_prefix(SAVE_KEY_PREFIX) {
+                if !ids.iter().any(|id| id == session_id) {
+                    ids.push(session_id.to_string());
+                }
+            }
+        }
+        ids
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Add a session to this browser's saved games, or take it off
+#[cfg(target_arch = "wasm32")]
+fn update_saved_game_ids(session_id: &str, keep: bool) {
+    use web_sys::window;
+
+    let mut ids = saved_game_ids();
+    ids.retain(|id| id != session_id);
+    if keep {
+        ids.push(session_id.to_string());
+    }
+    if let (Some(storage), Ok(json)) =
+        (window().and_then(|w| w.local_storage().ok().flatten()), serde_json::to_string(&ids))
+    {
+        let _ = storage.set_item(SAVED_GAMES_KEY, &json);
+    }
+}
+
+/// Load game state from localStorage, upgrading saves from older versions.
+/// `Ok(None)` if nothing was saved for this session.
+pub fn load_game_state(session_id: &str) -> Result<Option<GameState>, RestoreError> {
     #[cfg(target_arch = "wasm32")]
     {
         use web_sys::window;
@@ -56,79 +138,186 @@ pub fn load_game_state(session_id: &str) -> Option<GameState> {
         if let Ok(cache) = LAST_STATE.lock() {
             if let Some(state) = cache.as_ref() {
                 if state.session_id == session_id {
-                    return Some(state.clone());
+                    return Ok(Some(state.clone()));
                 }
             }
         }
         
-        let window = window()?;
-        let storage = window.local_storage().ok()??;
-        let key = format!("ultimate_imposter_game_{}", session_id);
-        let json = storage.get_item(&key).ok()??;
-        serde_json::from_str(&json).ok()
+        let key = format!("{}{}", SAVE_KEY_PREFIX, session_id);
+        let stored = window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(&key).ok().flatten());
+        match stored {
+            Some(json) => restore_game_state(&json).map(Some),
+            None => Ok(None),
+        }
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
         let _ = session_id;
-        None
+        Ok(None)
     }
 }
 
-/// Save game state to localStorage and optionally to server disk
+/// Save game state to localStorage and optionally to server disk, stamped with the time it was played
 pub fn save_game_state(_state: &GameState) {
     // Save to browser localStorage
     #[cfg(target_arch = "wasm32")]
     {
-        use web_sys::window;
-        
         // Avoid redundant writes if state JSON hasn't changed (saves time on mobile unlock)
         static LAST_SAVED_JSON: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
         static LAST_STATE: Lazy<Mutex<Option<GameState>>> = Lazy::new(|| Mutex::new(None));
 
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                if let Ok(json) = serde_json::to_string(_state) {
-                    let mut cache = LAST_SAVED_JSON.lock().unwrap_or_else(|e| e.into_inner());
-                    let is_new = cache.as_ref().map(|prev| prev != &json).unwrap_or(true);
-                    if is_new {
-                        let key = format!("ultimate_imposter_game_{}", _state.session_id);
-                        let _ = storage.set_item(&key, &json);
-                        *cache = Some(json);
-                        if let Ok(mut state_cache) = LAST_STATE.lock() {
-                            *state_cache = Some(_state.clone());
-                        }
-                    }
-                } else if let Ok(mut state_cache) = LAST_STATE.lock() {
-                    // Even if serialization failed, keep latest state in memory for fast resume
+        if let Ok(json) = serde_json::to_string(_state) {
+            let mut cache = LAST_SAVED_JSON.lock().unwrap_or_else(|e| e.into_inner());
+            let is_new = cache.as_ref().map(|prev| prev != &json).unwrap_or(true);
+            if is_new {
+                // Stamped after the comparison, so an unchanged game isn't rewritten just to move the clock
+                store_saved_game(&GameState { last_played_ms: now_ms(), .._state.clone() });
+
+                *cache = Some(json);
+                if let Ok(mut state_cache) = LAST_STATE.lock() {
                     *state_cache = Some(_state.clone());
                 }
             }
+        } else if let Ok(mut state_cache) = LAST_STATE.lock() {
+            // Even if serialization failed, keep latest state in memory for fast resume
+            *state_cache = Some(_state.clone());
+        }
+    }
+}
+
+/// Write a game to localStorage and the server as it is, e.g. after renaming it in the saved games list
+pub fn store_saved_game(_state: &GameState) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let Ok(json) = serde_json::to_string(_state) else {
+            return;
+        };
+        if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+            let key = format!("{}{}", SAVE_KEY_PREFIX, _state.session_id);
+            let _ = storage.set_item(&key, &json);
         }
+        update_saved_game_ids(&_state.session_id, true);
+
+        // Write through to the server so the game survives a cleared browser
+        let session_id = _state.session_id.clone();
+        spawn(async move {
+            let _ = save_game_to_disk(session_id, json, owner_token()).await;
+        });
     }
 }
 
+/// Forget a saved game, in this browser and on the server if this browser first saved it there.
+/// Finishes once the server has answered, so the list can be read again straight after.
+pub async fn delete_game_state(_session_id: &str) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+            let _ = storage.remove_item(&format!("{}{}", SAVE_KEY_PREFIX, _session_id));
+        }
+        update_saved_game_ids(_session_id, false);
+        let _ = delete_game_from_disk(_session_id.to_string(), owner_token()).await;
+    }
+}
+
+/// This browser's copy of a saved game, `None` if it only has the session ID
+fn load_local_saved_game(session_id: &str) -> Option<Result<GameState, RestoreError>> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let storage = window().and_then(|w| w.local_storage().ok().flatten())?;
+        let json = storage.get_item(&format!("{}{}", SAVE_KEY_PREFIX, session_id)).ok().flatten()?;
+        Some(restore_game_state(&json))
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = session_id;
+        None
+    }
+}
+
+/// Sessions the server holds that this browser first saved there, none if it can't be reached
+async fn server_saved_game_ids() -> Vec<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        list_saved_games(owner_token()).await.unwrap_or_default()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Every game this browser created or imported, plus the ones the server holds that it first saved
+/// there (e.g. from before its storage was cleared), from its own copy or else the server's,
+/// most recently played first. Saves that can't be restored are listed last so they can still be deleted.
+pub async fn load_saved_games() -> Vec<(String, Result<GameState, RestoreError>)> {
+    let mut session_ids = saved_game_ids();
+    for session_id in server_saved_game_ids().await {
+        if !session_ids.contains(&session_id) {
+            session_ids.push(session_id);
+        }
+    }
+
+    let mut games = Vec::new();
+    for session_id in session_ids {
+        let restored = match load_local_saved_game(&session_id) {
+            Some(restored) => Some(restored),
+            None => load_game_state_from_disk(&session_id).await.transpose(),
+        };
+        if let Some(restored) = restored {
+            let restored = restored.map(|state| GameState { session_id: session_id.clone(), ..state });
+            games.push((session_id, restored));
+        }
+    }
+    games.sort_by_key(|(_, game)| std::cmp::Reverse(game.as_ref().map_or(i64::MIN, |g| g.last_played_ms)));
+    games
+}
+
 // ============================================================================
 // Server Functions (for fullstack mode with disk persistence)
 // ============================================================================
 
-// Uncomment these when running in fullstack mode with server feature
-/*
-#[server(SaveGameToDisk)]
-async fn save_game_to_disk(session_id: String, game_state: String) -> Result<(), ServerFnError> {
-    crate::server::save_game_to_disk(&session_id, &game_state)
-        .map_err(|e| ServerFnError::ServerError(e))
+/// Save a serialized game state to the server's `game_saves` directory.
+/// The first browser to save a session is recorded as its owner, and only it can save over it.
+#[post("/api/games/{session_id}")]
+pub async fn save_game_to_disk(session_id: String, game_state: String, owner: String) -> Result<(), ServerFnError> {
+    crate::server::save_game_to_disk(&session_id, &game_state, &owner)
+        .map_err(ServerFnError::new)
+}
+
+/// Sessions in the server's `game_saves` directory that `owner` first saved
+#[post("/api/saved_games")]
+pub async fn list_saved_games(owner: String) -> Result<Vec<String>, ServerFnError> {
+    crate::server::list_saved_games(&owner)
+        .map_err(ServerFnError::new)
 }
 
-#[server(LoadGameFromDisk)]
-async fn load_game_from_disk(session_id: String) -> Result<String, ServerFnError> {
+/// Load a serialized game state from the server's `game_saves` directory
+#[get("/api/games/{session_id}")]
+pub async fn load_game_from_disk(session_id: String) -> Result<String, ServerFnError> {
     crate::server::load_game_from_disk(&session_id)
-        .map_err(|e| ServerFnError::ServerError(e))
+        .map_err(ServerFnError::new)
 }
 
-#[server(ListSavedGames)]
-async fn list_saved_games() -> Result<Vec<String>, ServerFnError> {
-    crate::server::list_saved_games()
-        .map_err(|e| ServerFnError::ServerError(e))
+/// Delete a session's save from the server's `game_saves` directory, if `owner` first saved it
+#[post("/api/games/{session_id}/delete")]
+pub async fn delete_game_from_disk(session_id: String, owner: String) -> Result<(), ServerFnError> {
+    crate::server::delete_saved_game(&session_id, &owner)
+        .map_err(ServerFnError::new)
 }
-*/
 
+/// Fetch the server's copy of a session, upgrading it if it's from an older version.
+/// `Ok(None)` if the server has no copy (or can't be reached).
+pub async fn load_game_state_from_disk(session_id: &str) -> Result<Option<GameState>, RestoreError> {
+    match load_game_from_disk(session_id.to_string()).await {
+        Ok(json) => restore_game_state(&json).map(Some),
+        Err(_) => Ok(None),
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+// ============================================================================
+// Random numbers - every draw the game makes goes through here
+// ============================================================================
+
+/// Source of random numbers for dealing cards and picking players
+pub trait GameRng {
+    fn next_u64(&mut self) -> u64;
+}
+
+/// The OS (or the browser's crypto API on wasm) couldn't provide randomness
+#[derive(Clone, PartialEq, Debug)]
+pub struct EntropyError(pub String);
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No randomness available: {}", self.0)
+    }
+}
+
+impl std::error::Error for EntropyError {}
+
+/// Fresh seed from the OS. Fails loudly rather than falling back to zeros,
+/// which would make the same player the imposter every time.
+pub fn entropy_seed() -> Result<u64, EntropyError> {
+    let mut buf = [0u8; 8];
+    getrandom::getrandom(&mut buf).map_err(|e| EntropyError(e.to_string()))?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Uniform index in `0..n` without modulo bias.
+///
+/// Draws that land in the short, uneven tail of the `u64` range are thrown away and redrawn,
+/// so every index has exactly the same number of accepted values. `n` must be non-zero.
+pub fn uniform_index(rng: &mut dyn GameRng, n: usize) -> usize {
+    uniform_below(rng, n as u64) as usize
+}
+
+fn uniform_below(rng: &mut dyn GameRng, n: u64) -> u64 {
+    // 2^64 mod n: the number of values at the bottom of the range to reject
+    let reject_below = n.wrapping_neg() % n;
+    loop {
+        let value = rng.next_u64();
+        if value >= reject_below {
+            return value % n;
+        }
+    }
+}
+
+/// Index picked with probability proportional to its weight.
+/// Falls back to a uniform pick if every weight is zero. `weights` must not be empty.
+pub fn weighted_index(rng: &mut dyn GameRng, weights: &[u64]) -> usize {
+    let total: u64 = weights.iter().sum();
+    if total == 0 {
+        return uniform_index(rng, weights.len());
+    }
+    let mut target = uniform_below(rng, total);
+    for (index, &weight) in weights.iter().enumerate() {
+        if target < weight {
+            return index;
+        }
+        target -= weight;
+    }
+    unreachable!("target is below the total weight")
+}
+
+/// Fair coin flip
+pub fn coin_flip(rng: &mut dyn GameRng) -> bool {
+    rng.next_u64() & 1 == 1
+}
+
+/// SplitMix64: tiny, fast and fully determined by its state, so a seeded game can be replayed.
+/// Not suitable for anything security related.
+#[derive(Clone, Debug)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(state: u64) -> Self {
+        SeededRng { state }
+    }
+
+    /// Current position in the stream; feed it back into `new` to carry on where this left off
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+impl GameRng for SeededRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Turn whatever the host typed into a seed. Numbers are used as-is so seeds can be
+/// copied from the score screen; any other text is hashed. Blank means "no seed".
+pub fn parse_seed(input: &str) -> Option<u64> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    if let Ok(seed) = input.parse::<u64>() {
+        return Some(seed);
+    }
+    // FNV-1a, so the same phrase gives the same seed on every platform
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in input.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays a fixed list of values
+    struct Scripted(Vec<u64>);
+
+    impl GameRng for Scripted {
+        fn next_u64(&mut self) -> u64 {
+            self.0.remove(0)
+        }
+    }
+
+    #[test]
+    fn uniform_index_rejects_biased_tail() {
+        // 2^64 mod 3 == 1, so 0 is the one value that would favour index 0
+        let mut rng = Scripted(vec![0, 0, 5]);
+        assert_eq!(uniform_index(&mut rng, 3), 2);
+        assert!(rng.0.is_empty());
+    }
+
+    #[test]
+    fn uniform_index_accepts_everything_for_powers_of_two() {
+        let mut rng = Scripted(vec![0, u64::MAX]);
+        assert_eq!(uniform_index(&mut rng, 4), 0);
+        assert_eq!(uniform_index(&mut rng, 4), 3);
+    }
+
+    #[test]
+    fn weighted_index_skips_zero_weights() {
+        let mut rng = SeededRng::new(3);
+        for _ in 0..1_000 {
+            assert_ne!(weighted_index(&mut rng, &[0, 4, 1, 0]), 0);
+        }
+        assert_eq!(weighted_index(&mut Scripted(vec![2]), &[0, 0, 0]), 2);
+    }
+
+    #[test]
+    fn seeded_rng_is_deterministic() {
+        let (mut a, mut b) = (SeededRng::new(7), SeededRng::new(7));
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_eq!(a.state(), b.state());
+        assert_ne!(SeededRng::new(7).next_u64(), SeededRng::new(8).next_u64());
+    }
+}
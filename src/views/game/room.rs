@@ -0,0 +1,433 @@
+use crate::views::game::engine::GameAction;
+use crate::views::game::types::{GameScreen, GameState};
+use dioxus::fullstack::{WebSocketOptions, Websocket};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(any(feature = "server", test))]
+use crate::views::game::engine::match_winners;
+#[cfg(any(feature = "server", test))]
+use crate::views::game::types::{CardType, GameCard, GameEvent};
+
+// ============================================================================
+// Multi-device rooms: one shared game, every player on their own phone
+// ============================================================================
+
+/// localStorage key prefix for the seat secrets this phone holds, by room code and name
+#[cfg(target_arch = "wasm32")]
+const SEAT_SECRET_PREFIX: &str = "ultimate_imposter_seat_";
+
+/// What the host gets back when opening a room
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RoomTicket {
+    pub code: String,
+    pub host_token: String, // Only the connection holding this may drive the game
+}
+
+/// Sent by a connected device to the room
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Only honoured from the host connection
+    Action(GameAction),
+    /// A player's secret vote, from their own phone
+    Vote { target: usize },
+}
+
+/// Sent by the room to a connected device
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// JSON merge patch (RFC 7396) against the last view this device received
+    Patch(Value),
+    /// The whole view, when the change sets something to null, which a merge patch can't say
+    Snapshot(Value),
+    /// The host's last action was rejected by the engine
+    Rejected(String),
+}
+
+/// Find a player's seat by name, ignoring case and surrounding whitespace.
+/// Until the game starts, the seats are the names the host has typed in.
+pub fn seat_index(state: &GameState, name: &str) -> Option<usize> {
+    let name = name.trim();
+    let is_named = |seat: &str| seat.trim().eq_ignore_ascii_case(name);
+    if state.game_screen == GameScreen::Setup {
+        return state.player_names.iter().position(|n| is_named(n));
+    }
+    state.players.iter().position(|p| is_named(&p.name))
+}
+
+/// The secret this phone was given for a seat, so a reload can sit back down in it
+pub fn load_seat_secret(_code: &str, _name: &str) -> Option<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let key = format!("{}{}_{}", SEAT_SECRET_PREFIX, _code.trim().to_ascii_uppercase(), _name.trim().to_ascii_lowercase());
+        window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(&key).ok().flatten())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        None
+    }
+}
+
+/// Remember the secret for a seat this phone has claimed
+pub fn store_seat_secret(_code: &str, _name: &str, _secret: &str) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let key = format!("{}{}_{}", SEAT_SECRET_PREFIX, _code.trim().to_ascii_uppercase(), _name.trim().to_ascii_lowercase());
+        if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+            let _ = storage.set_item(&key, _secret);
+        }
+    }
+}
+
+/// The part of the room's state a device is allowed to see.
+///
+/// `seat` is the player's name, or `None` for the host. Until the round is over nobody
+/// sees another player's word, who the imposter or Mr. White is or the word pair,
+/// and the seed stays hidden until the match is won.
+#[cfg(any(feature = "server", test))]
+pub fn view_for(state: &GameState, seat: Option<&str>) -> GameState {
+    let mut view = state.clone();
+    if matches!(state.game_screen, GameScreen::RoundEnd { .. } | GameScreen::GameScore | GameScreen::History) {
+        // The seed would replay the rounds still to come, so it waits until the match is won
+        if match_winners(state).is_empty() {
+            view.hide_seed();
+        }
+        return view;
+    }
+
+    let own_seat = seat.and_then(|name| seat_index(state, name));
+    view.cards = state
+        .cards
+        .iter()
+        .enumerate()
+        .map(|(i, card)| {
+            if Some(i) == own_seat && (!state.hide_imposter_identity || card.card_type != CardType::Imposter) {
+                // Mr. White can tell from the missing word anyway, so hard mode only hides imposters
+                card.clone()
+            } else if Some(i) == own_seat {
+                // Hard mode: the imposter must not be able to tell from the payload either
+                GameCard { card_type: CardType::Normal, word: card.word.clone() }
+            } else {
+                GameCard { card_type: CardType::Normal, word: String::new() }
+            }
+        })
+        .collect();
+    view.imposter_indices.clear();
+    // Everyone can see who has voted, but only their own choice until the count
+    for (voter, choice) in view.ballots.iter_mut() {
+        if Some(*voter) != own_seat {
+            *choice = None;
+        }
+    }
+    view.mr_white_indices.clear();
+    view.current_round_words = None;
+    // A guess that hit would give the word away while other imposters are still playing
+    view.guesses.clear();
+    view.hide_seed();
+    // The newest used pair is the one in play, and its ID gives the words away.
+    // Orientation history only matters to the engine, so it isn't sent at all.
+    if !state.cards.is_empty() {
+        if let Some(used) = state.selected_category_id.as_ref().and_then(|id| view.used_pairs.get_mut(id)) {
+            used.pop();
+        }
+    }
+    view.pair_orientation.clear();
+    // The log's current round names the words and the imposters, so it stops at the last deal
+    if !state.cards.is_empty() {
+        if let Some(deal) = view.history.iter().rposition(|e| matches!(e, GameEvent::RoundStarted { .. })) {
+            view.history.truncate(deal);
+        }
+    }
+    // Fair deal bookkeeping would point straight at this round's imposters
+    for player in view.players.iter_mut() {
+        player.times_imposter = 0;
+        player.imposter_streak = 0;
+    }
+    view
+}
+
+/// Smallest JSON merge patch turning `old` into `new`, or `None` if nothing changed
+#[cfg(any(feature = "server", test))]
+pub fn merge_diff(old: &Value, new: &Value) -> Option<Value> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut patch = Map::new();
+            for (key, value) in new {
+                match old.get(key) {
+                    Some(previous) => {
+                        if let Some(changed) = merge_diff(previous, value) {
+                            patch.insert(key.clone(), changed);
+                        }
+                    }
+                    None => {
+                        patch.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            for key in old.keys() {
+                if !new.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+            (!patch.is_empty()).then_some(Value::Object(patch))
+        }
+        _ if old == new => None,
+        _ => Some(new.clone()),
+    }
+}
+
+/// Apply a JSON merge patch produced by `merge_diff`
+pub fn merge_apply(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(map) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                map.remove(key);
+            } else {
+                merge_apply(map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// What to send a device whose last view was `old` so it ends up with `new`,
+/// or `None` if nothing changed
+#[cfg(any(feature = "server", test))]
+pub fn view_update(old: &Value, new: &Value) -> Option<ServerMessage> {
+    let patch = merge_diff(old, new)?;
+    let mut patched = old.clone();
+    merge_apply(&mut patched, &patch);
+    // A hidden vote is a null, and a merge patch would drop it rather than show who has voted
+    Some(if patched == *new { ServerMessage::Patch(patch) } else { ServerMessage::Snapshot(new.clone()) })
+}
+
+// ============================================================================
+// Server Functions
+// ============================================================================
+
+/// Open a new room and return its join code, with the token that makes this device its host
+#[post("/api/rooms")]
+pub async fn create_room() -> Result<RoomTicket, ServerFnError> {
+    crate::rooms::create_room().map_err(ServerFnError::new)
+}
+
+/// Claim the seat for `name` in a room and return the secret that connects to it
+#[post("/api/rooms/{code}/seats")]
+pub async fn join_room(code: String, name: String) -> Result<String, ServerFnError> {
+    crate::rooms::claim_seat(&code, &name).map_err(ServerFnError::new)
+}
+
+/// Live connection to a room. The host connects without a seat, with the token from
+/// `create_room`, and drives the game. Players connect with their name and the secret from
+/// `join_room`, only ever receive their own card, and can only vote.
+#[get("/api/rooms/{code}/ws?seat&token")]
+pub async fn room_socket(
+    code: String,
+    seat: Option<String>,
+    token: String,
+    options: WebSocketOptions,
+) -> Result<Websocket<ClientMessage, ServerMessage>> {
+    use crate::rooms::RoomAccess;
+
+    let (access, mut updates) =
+        crate::rooms::subscribe_room(&code, seat.as_deref(), &token).or_not_found("Room not found")?;
+    let seat = match &access {
+        RoomAccess::Host => None,
+        RoomAccess::Seat(name) => Some(name.clone()),
+    };
+
+    Ok(options.on_upgrade(move |mut socket| async move {
+        let mut sent = Value::Null;
+        loop {
+            // Push whatever changed in this device's view since the last message
+            let view = view_for(&updates.borrow_and_update(), seat.as_deref());
+            let view = serde_json::to_value(&view).unwrap_or(Value::Null);
+            if let Some(update) = view_update(&sent, &view) {
+                if socket.send(update).await.is_err() {
+                    break;
+                }
+                sent = view;
+            }
+
+            let incoming = tokio::select! {
+                changed = updates.changed() => match changed {
+                    Ok(()) => None,
+                    Err(_) => break,
+                },
+                message = socket.recv() => match message {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                },
+            };
+
+            // Only the host's connection drives the game, and players only ever vote as themselves
+            let action = match (incoming, &access) {
+                (Some(ClientMessage::Action(action)), RoomAccess::Host) => action,
+                (Some(ClientMessage::Vote { target }), RoomAccess::Seat(name)) => {
+                    let Some(voter) = seat_index(&updates.borrow(), name) else {
+                        continue;
+                    };
+                    GameAction::CastVote { voter, target }
+                }
+                _ => continue,
+            };
+            if let Err(e) = crate::rooms::apply_room_action(&code, action) {
+                if socket.send(ServerMessage::Rejected(e.to_string())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::engine::GameEngine;
+    use serde_json::json;
+
+    const NAMES: [&str; 5] = ["Ann", "Bob", "Cat", "Dan", "Eve"];
+    const CATEGORY: &str = "builtin/food-drinks";
+
+    /// A room's game with the first round dealt: one imposter and one Mr. White
+    fn dealt(hard_mode: bool) -> GameEngine {
+        let mut engine = GameEngine::new(GameState::new("ROOM1".to_string()));
+        engine.dispatch(GameAction::SetPlayerCount(NAMES.len().to_string())).unwrap();
+        for (index, name) in NAMES.iter().enumerate() {
+            engine.dispatch(GameAction::SetPlayerName { index, name: name.to_string() }).unwrap();
+        }
+        engine.dispatch(GameAction::SetSeed(Some(7))).unwrap();
+        engine.dispatch(GameAction::SetImposterCount(Some(1))).unwrap();
+        engine.dispatch(GameAction::SetMrWhiteCount(1)).unwrap();
+        engine.dispatch(GameAction::StartGame).unwrap();
+        engine.dispatch(GameAction::PickCategory { category_id: CATEGORY.to_string() }).unwrap();
+        engine.dispatch(GameAction::SetHardMode(hard_mode)).unwrap();
+        engine.dispatch(GameAction::StartRound).unwrap();
+        engine
+    }
+
+    #[test]
+    fn a_seat_sees_only_its_own_card() {
+        let state = dealt(false).state().clone();
+        let (civilian, imposter) = state.current_round_words.clone().unwrap();
+        let in_play = state.used_pairs[CATEGORY].last().unwrap().clone();
+        let deal = state.history.iter().rposition(|e| matches!(e, GameEvent::RoundStarted { .. })).unwrap();
+
+        for seat in NAMES.iter().map(|name| Some(*name)).chain([None]) {
+            let view = view_for(&state, seat);
+            let own_seat = seat.and_then(|name| seat_index(&state, name));
+            for (i, card) in view.cards.iter().enumerate() {
+                if Some(i) == own_seat {
+                    assert_eq!(card, &state.cards[i]);
+                } else {
+                    assert_eq!(card, &GameCard { card_type: CardType::Normal, word: String::new() });
+                }
+            }
+            assert!(view.imposter_indices.is_empty() && view.mr_white_indices.is_empty());
+            assert_eq!(view.current_round_words, None);
+            assert!(view.pair_orientation.is_empty());
+            // The log stops at the deal, and the seed that would replay it is hidden
+            assert_eq!(view.history.len(), deal);
+            assert_eq!((view.seed, view.game_seed, view.rng_state), (None, 0, 0));
+            assert!(!view.used_pairs[CATEGORY].contains(&in_play));
+
+            // Nothing else in what's sent gives away a word this seat wasn't dealt
+            let json = serde_json::to_string(&view).unwrap();
+            let own_word = own_seat.map(|i| state.cards[i].word.clone()).unwrap_or_default();
+            for word in [&civilian, &imposter] {
+                assert_eq!(json.contains(&format!("\"{}\"", word)), *word == own_word, "{:?} sees {}", seat, word);
+            }
+        }
+    }
+
+    #[test]
+    fn hard_mode_hides_the_imposter_flag() {
+        let state = dealt(true).state().clone();
+        let (_, imposter_word) = state.current_round_words.clone().unwrap();
+        let imposter = *state.imposter_indices.iter().next().unwrap();
+        let mr_white = *state.mr_white_indices.iter().next().unwrap();
+
+        let view = view_for(&state, Some(NAMES[imposter]));
+        assert_eq!(view.cards[imposter], GameCard { card_type: CardType::Normal, word: imposter_word });
+        // Mr. White's missing word gives the role away anyway
+        let view = view_for(&state, Some(NAMES[mr_white]));
+        assert_eq!(view.cards[mr_white].card_type, CardType::MrWhite);
+    }
+
+    #[test]
+    fn the_seed_is_shown_once_a_new_game_starts() {
+        let mut state = dealt(false).into_state();
+        state.game_screen = GameScreen::RoundEnd { imposter_found: true, game_over: true, stolen_by: None };
+        let mut engine = GameEngine::new(state);
+        engine.dispatch(GameAction::NewGame).unwrap();
+
+        let view = view_for(engine.state(), Some(NAMES[0]));
+        assert_eq!(view.last_game_seed, Some(7));
+        assert_eq!(view.seed, None);
+    }
+
+    #[test]
+    fn merge_patches_round_trip() {
+        let old = json!({
+            "round_number": 1,
+            "players": [{"name": "Ann"}],
+            "used_pairs": {"a": ["p1"], "b": ["p2"]},
+            "timer": {"phase": "Discussion", "paused": false},
+        });
+        let new = json!({
+            "round_number": 2,
+            "players": [{"name": "Ann"}, {"name": "Bob"}],
+            "used_pairs": {"a": ["p1", "p3"]},
+            "selected_category_id": "builtin/food-drinks",
+        });
+        for (old, new) in [(&old, &new), (&new, &old), (&Value::Null, &new)] {
+            let patch = merge_diff(old, new).unwrap();
+            let mut patched = old.clone();
+            merge_apply(&mut patched, &patch);
+            assert_eq!(&patched, new);
+        }
+        assert_eq!(merge_diff(&new, &new), None);
+        // Removed keys are all the patch says about them
+        assert_eq!(merge_diff(&old, &new).unwrap()["used_pairs"], json!({"a": ["p1", "p3"], "b": null}));
+    }
+
+    #[test]
+    fn views_stay_in_sync_through_a_secret_vote() {
+        let mut engine = dealt(false);
+        engine.dispatch(GameAction::ProceedToVoting).unwrap();
+        let mut actions = vec![GameAction::OpenBallot];
+        actions.extend((0..NAMES.len()).map(|voter| GameAction::CastVote { voter, target: (voter + 1) % NAMES.len() }));
+
+        let mut sent = Value::Null;
+        let mut received = Value::Null;
+        for action in actions {
+            let view = serde_json::to_value(view_for(engine.state(), Some("Bob"))).unwrap();
+            match view_update(&sent, &view) {
+                Some(ServerMessage::Patch(patch)) => merge_apply(&mut received, &patch),
+                Some(ServerMessage::Snapshot(view)) => received = view,
+                Some(ServerMessage::Rejected(_)) => unreachable!(),
+                None => {}
+            }
+            assert_eq!(received, view);
+            // Everyone can see who has voted, even though only Bob's own choice is shown
+            let ballots = serde_json::from_value::<GameState>(received.clone()).unwrap().ballots;
+            assert!(ballots.keys().eq(engine.state().ballots.keys()));
+            assert!(ballots.iter().all(|(&voter, choice)| choice.is_some() == (voter == 1)));
+            sent = view;
+            engine.dispatch(action).unwrap();
+        }
+    }
+}
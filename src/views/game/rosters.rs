@@ -0,0 +1,117 @@
+use crate::views::game::engine::MIN_PLAYERS;
+use crate::views::game::types::{GameState, Roster, RosterPlayer};
+
+// ============================================================================
+// Saved rosters - groups of players kept in this browser
+// ============================================================================
+
+/// localStorage key holding the saved rosters
+#[cfg(target_arch = "wasm32")]
+const ROSTERS_KEY: &str = "ultimate_imposter_rosters";
+
+/// The setup screen's players as a roster called `name`, in seating order.
+///
+/// Blank slots are left out. `None` if the roster has no name or too few players to play.
+pub fn roster_from_setup(name: &str, state: &GameState) -> Option<Roster> {
+    let name = name.trim();
+    let players: Vec<RosterPlayer> = state
+        .player_names
+        .iter()
+        .enumerate()
+        .filter(|(_, player)| !player.trim().is_empty())
+        .map(|(i, player)| RosterPlayer {
+            name: player.trim().to_string(),
+            id: state.player_ids.get(i).cloned().unwrap_or_default(),
+        })
+        .collect();
+    (!name.is_empty() && players.len() >= MIN_PLAYERS).then(|| Roster { name: name.to_string(), players })
+}
+
+/// Rosters saved in this browser
+pub fn load_rosters() -> Vec<Roster> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let stored = window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(ROSTERS_KEY).ok().flatten());
+        stored
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Vec::new()
+    }
+}
+
+fn store_rosters(_rosters: &[Roster]) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        if let Some(window) = window() {
+            if let Ok(Some(storage)) = window.local_storage() {
+                if let Ok(json) = serde_json::to_string(_rosters) {
+                    let _ = storage.set_item(ROSTERS_KEY, &json);
+                }
+            }
+        }
+    }
+}
+
+/// Add a roster to the list, replacing one with the same name (ignoring case)
+pub fn put_roster(rosters: &mut Vec<Roster>, roster: Roster) {
+    rosters.retain(|r| !r.name.eq_ignore_ascii_case(&roster.name));
+    rosters.push(roster);
+    rosters.sort_by_key(|r| r.name.to_lowercase());
+}
+
+/// Keep a roster in this browser and return every saved roster
+pub fn save_roster(roster: Roster) -> Vec<Roster> {
+    let mut rosters = load_rosters();
+    put_roster(&mut rosters, roster);
+    store_rosters(&rosters);
+    rosters
+}
+
+/// Forget a saved roster and return the ones left
+pub fn remove_roster(name: &str) -> Vec<Roster> {
+    let mut rosters = load_rosters();
+    rosters.retain(|r| r.name != name);
+    store_rosters(&rosters);
+    rosters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_slots_are_left_out() {
+        let mut state = GameState::new(String::new());
+        state.player_names = ["Ann ", "", "Bob", "Cat"].map(String::from).to_vec();
+        state.player_ids = ["a", "", "", "c"].map(String::from).to_vec();
+        let roster = roster_from_setup(" Friday crew ", &state).unwrap();
+        assert_eq!(roster.name, "Friday crew");
+        let players: Vec<(&str, &str)> = roster.players.iter().map(|p| (p.name.as_str(), p.id.as_str())).collect();
+        assert_eq!(players, vec![("Ann", "a"), ("Bob", ""), ("Cat", "c")]);
+
+        assert!(roster_from_setup("   ", &state).is_none());
+        state.player_names[3].clear();
+        assert!(roster_from_setup("Friday crew", &state).is_none());
+    }
+
+    #[test]
+    fn saving_replaces_a_roster_of_the_same_name() {
+        let roster = |name: &str, size: usize| Roster {
+            name: name.to_string(),
+            players: vec![RosterPlayer { name: "Ann".to_string(), id: String::new() }; size],
+        };
+        let mut rosters = vec![roster("Friday crew", 3), roster("Book club", 4)];
+        put_roster(&mut rosters, roster("friday CREW", 5));
+        let summary: Vec<(&str, usize)> = rosters.iter().map(|r| (r.name.as_str(), r.players.len())).collect();
+        assert_eq!(summary, vec![("Book club", 4), ("friday CREW", 5)]);
+    }
+}
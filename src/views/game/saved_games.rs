@@ -0,0 +1,163 @@
+use crate::views::game::types::GameState;
+
+// ============================================================================
+// Saved games - what the resume picker shows about each session
+// ============================================================================
+
+/// One line of the saved games list
+#[derive(Clone, PartialEq, Debug)]
+pub struct SavedGameSummary {
+    pub session_id: String,
+    pub name: String,
+    pub last_played_ms: i64,
+    pub round_number: i32,
+    pub started: bool, // Past the setup screen, so there are scores to show
+    pub standings: Vec<(String, i32)>, // Player name and score, highest first
+}
+
+/// The name a game goes by: the one it was given, or who is playing
+pub fn game_name(state: &GameState) -> String {
+    if !state.save_name.trim().is_empty() {
+        return state.save_name.trim().to_string();
+    }
+    let names: Vec<&str> = state.player_names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()).collect();
+    match names.as_slice() {
+        [] => "New game".to_string(),
+        [only] => only.to_string(),
+        [rest @ .., last] if names.len() <= 4 => format!("{} & {}", rest.join(", "), last),
+        _ => format!("{} & {} more", names[..3].join(", "), names.len() - 3),
+    }
+}
+
+/// What the saved games list shows about a game
+pub fn summarize(state: &GameState) -> SavedGameSummary {
+    let mut standings: Vec<(String, i32)> = state.players.iter().map(|p| (p.name.clone(), p.score)).collect();
+    standings.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    SavedGameSummary {
+        session_id: state.session_id.clone(),
+        name: game_name(state),
+        last_played_ms: state.last_played_ms,
+        round_number: state.round_number,
+        started: !state.players.is_empty(),
+        standings,
+    }
+}
+
+/// How long ago `then_ms` was, in words
+pub fn played_ago(now_ms: i64, then_ms: i64) -> String {
+    if then_ms <= 0 {
+        return "a while ago".to_string();
+    }
+    let minutes = (now_ms - then_ms).max(0) / 60_000;
+    match minutes {
+        0 => "just now".to_string(),
+        1..=59 => format!("{} min ago", minutes),
+        60..=1439 => format!("{} h ago", minutes / 60),
+        1440..=2879 => "yesterday".to_string(),
+        _ => format!("{} days ago", minutes / 1440),
+    }
+}
+
+/// A copy of a saved game to carry on separately, under a new session ID
+pub fn duplicate_game(state: &GameState, session_id: String) -> GameState {
+    GameState {
+        session_id,
+        save_name: format!("{} (copy)", game_name(state)),
+        ..state.clone()
+    }
+}
+
+/// The setup for the next game, as a session of its own so the last one stays in the list.
+/// Only the players and the settings chosen at setup carry over: nothing about the last
+/// game's deals, votes or words does, and its seed stays behind so the next game isn't a replay.
+pub fn next_game_session(state: GameState, session_id: String) -> GameState {
+    GameState {
+        player_count_input: state.player_count_input,
+        player_cap: state.player_cap,
+        player_names: state.player_names,
+        player_ids: state.player_ids,
+        imposter_count: state.imposter_count,
+        mr_white_count: state.mr_white_count,
+        hide_imposter_identity: state.hide_imposter_identity,
+        disabled_packs: state.disabled_packs,
+        fair_deal: state.fair_deal,
+        max_imposter_streak: state.max_imposter_streak,
+        imposter_last_chance: state.imposter_last_chance,
+        ballot_mode: state.ballot_mode,
+        tie_break: state.tie_break,
+        scoring: state.scoring,
+        timers: state.timers,
+        last_game_seed: state.last_game_seed,
+        ..GameState::new(session_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::types::{GameEvent, VoteOutcome, VoteTally};
+
+    fn with_names(names: &[&str]) -> GameState {
+        let mut state = GameState::new("s".to_string());
+        state.player_names = names.iter().map(|n| n.to_string()).collect();
+        state
+    }
+
+    #[test]
+    fn games_are_named_after_their_players() {
+        assert_eq!(game_name(&with_names(&[])), "New game");
+        assert_eq!(game_name(&with_names(&["Ann", " ", "Bob", "Cat"])), "Ann, Bob & Cat");
+        assert_eq!(game_name(&with_names(&["A", "B", "C", "D", "E", "F"])), "A, B, C & 3 more");
+        let mut named = with_names(&["Ann"]);
+        named.save_name = " Friday ".to_string();
+        assert_eq!(game_name(&named), "Friday");
+        assert_eq!(duplicate_game(&named, "t".to_string()).save_name, "Friday (copy)");
+    }
+
+    #[test]
+    fn the_next_game_keeps_only_the_setup() {
+        let mut old = with_names(&["Ann", "Bob", "Cat"]);
+        old.save_name = "Friday".to_string();
+        old.ballot_mode = true;
+        old.mr_white_count = 1;
+        old.seed = Some(7);
+        old.game_seed = 7;
+        old.rng_state = 99;
+        old.last_game_seed = Some(7);
+        old.used_pairs.insert("builtin/food".to_string(), vec!["p1".to_string()]);
+        old.pair_orientation.insert("builtin/food".to_string(), [("p1".to_string(), true)].into());
+        old.vote_tallies.push(VoteTally {
+            round_number: 1,
+            ballots: Default::default(),
+            outcome: VoteOutcome::NoEviction { tied: Vec::new() },
+        });
+        old.ballots.insert(0, Some(1));
+        old.revote_candidates = vec![0, 1];
+        old.current_round_words = Some(("Coffee".to_string(), "Tea".to_string()));
+        old.history.push(GameEvent::RoundAbandoned);
+
+        let next = next_game_session(old, "t".to_string());
+        assert_eq!(next.session_id, "t");
+        assert_eq!(next.player_names, vec!["Ann", "Bob", "Cat"]);
+        assert!(next.ballot_mode);
+        assert_eq!(next.mr_white_count, 1);
+        assert!(next.save_name.is_empty() && next.history.is_empty());
+        assert!(next.used_pairs.is_empty() && next.pair_orientation.is_empty());
+        assert!(next.vote_tallies.is_empty() && next.ballots.is_empty() && next.revote_candidates.is_empty());
+        assert_eq!(next.current_round_words, None);
+        assert_eq!((next.seed, next.game_seed, next.rng_state), (None, 0, 0));
+        // The finished game's seed is still shown while the next one is set up
+        assert_eq!(next.last_game_seed, Some(7));
+    }
+
+    #[test]
+    fn last_played_reads_naturally() {
+        let minute = 60_000;
+        assert_eq!(played_ago(100 * minute, 0), "a while ago");
+        assert_eq!(played_ago(100 * minute, 100 * minute - 5_000), "just now");
+        assert_eq!(played_ago(100 * minute, 95 * minute), "5 min ago");
+        assert_eq!(played_ago(5000 * minute, 5000 * minute - 180 * minute), "3 h ago");
+        assert_eq!(played_ago(5000 * minute, 5000 * minute - 1500 * minute), "yesterday");
+        assert_eq!(played_ago(9000 * minute, 9000 * minute - 4400 * minute), "3 days ago");
+    }
+}
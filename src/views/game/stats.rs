@@ -0,0 +1,343 @@
+use crate::views::game::types::{CardType, GameEvent, GameState, PlayerRecord, PlayerRegistry, VoteOutcome, VoteTally};
+use std::collections::BTreeSet;
+
+// ============================================================================
+// Lifetime player stats - folded in from each session's history log
+// ============================================================================
+
+/// localStorage key holding the player registry
+#[cfg(target_arch = "wasm32")]
+const PLAYER_REGISTRY_KEY: &str = "ultimate_imposter_players";
+
+impl PlayerRecord {
+    /// Share of finished rounds as an imposter or Mr. White that they won, if they've had any
+    pub fn imposter_win_rate(&self) -> Option<f64> {
+        (self.times_imposter > 0).then(|| f64::from(self.imposter_wins) / f64::from(self.times_imposter))
+    }
+
+    /// Categories they've played most, most played first
+    pub fn favourite_categories(&self, count: usize) -> Vec<String> {
+        let mut categories: Vec<(&String, &u32)> = self.categories.iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(a.1));
+        categories.into_iter().take(count).map(|(name, _)| name.clone()).collect()
+    }
+}
+
+impl PlayerRegistry {
+    /// The player who last played under this name, ignoring case and stray spaces
+    pub fn find_by_name(&self, name: &str) -> Option<&PlayerRecord> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        self.players.values().find(|record| record.name.trim().eq_ignore_ascii_case(name))
+    }
+
+    fn record(&mut self, id: &str) -> Option<&mut PlayerRecord> {
+        self.players.get_mut(id)
+    }
+
+    /// Count a game for someone sitting down to it, under the name they're playing as
+    fn joined(&mut self, id: &str, name: &str) {
+        let record = self.players.entry(id.to_string()).or_insert_with(|| PlayerRecord {
+            id: id.to_string(),
+            ..PlayerRecord::default()
+        });
+        record.name = name.to_string();
+        record.games_played += 1;
+    }
+}
+
+/// Keep the IDs of the players at the table in seat order as people start, join and leave
+fn follow_seating(ids: &mut Vec<String>, event: &GameEvent) {
+    match event {
+        GameEvent::GameStarted { player_ids, .. } => *ids = player_ids.clone(),
+        GameEvent::PlayerJoined { id, .. } => ids.push(id.clone()),
+        GameEvent::PlayerLeft { player_index } if *player_index < ids.len() => {
+            ids.remove(*player_index);
+        }
+        _ => {}
+    }
+}
+
+/// What the fold needs to remember about the round it's in the middle of
+#[derive(Default)]
+struct RoundInProgress {
+    category: String,
+    secret: BTreeSet<usize>,
+    evicted: BTreeSet<usize>,
+    last_vote: Option<VoteTally>,
+}
+
+/// Count any rounds of this session's history that the registry hasn't seen yet.
+///
+/// Only whole rounds are counted, so a round in play is picked up once it has a result,
+/// and counting the same history again changes nothing. Returns whether anything was added.
+pub fn record_history(registry: &mut PlayerRegistry, session_id: &str, history: &[GameEvent]) -> bool {
+    let start = registry.recorded.get(session_id).copied().unwrap_or(0).min(history.len());
+    let end = history
+        .iter()
+        .rposition(|event| {
+            matches!(
+                event,
+                GameEvent::GameStarted { .. }
+                    | GameEvent::RoundEnded { .. }
+                    | GameEvent::RoundAbandoned
+                    | GameEvent::PlayerJoined { .. }
+                    | GameEvent::PlayerLeft { .. }
+            )
+        })
+        .map_or(0, |i| i + 1);
+    if end <= start {
+        return false;
+    }
+
+    // Who the indices refer to, for a session picked up part way through a game
+    let mut ids: Vec<String> = Vec::new();
+    for event in &history[..start] {
+        follow_seating(&mut ids, event);
+    }
+    let mut round = RoundInProgress::default();
+
+    for event in &history[start..end] {
+        match event {
+            GameEvent::GameStarted { players, player_ids, .. } => {
+                for (id, name) in player_ids.iter().zip(players) {
+                    registry.joined(id, name);
+                }
+            }
+            GameEvent::PlayerJoined { name, id, .. } => {
+                registry.joined(id, name);
+            }
+            GameEvent::RoundStarted { category_name, imposters, mr_whites, .. } => {
+                round = RoundInProgress {
+                    category: category_name.clone(),
+                    secret: imposters.iter().chain(mr_whites).copied().collect(),
+                    ..RoundInProgress::default()
+                };
+            }
+            GameEvent::VoteCounted(tally) => {
+                round.last_vote = Some(tally.clone());
+            }
+            GameEvent::Evicted { player_index, card_type } => {
+                let evicted = *player_index;
+                if *card_type == CardType::Normal {
+                    if let Some(record) = ids.get(evicted).and_then(|id| registry.record(id)) {
+                        record.wrongly_evicted += 1;
+                    }
+                } else {
+                    for voter in voters_against(&round, ids.len(), evicted) {
+                        if let Some(record) = ids.get(voter).and_then(|id| registry.record(id)) {
+                            record.correct_evictions += 1;
+                        }
+                    }
+                }
+                round.evicted.insert(evicted);
+                round.last_vote = None;
+            }
+            GameEvent::RoundEnded { imposter_found, stolen_by, .. } => {
+                for (i, id) in ids.iter().enumerate() {
+                    let Some(record) = registry.record(id) else {
+                        continue;
+                    };
+                    record.rounds_played += 1;
+                    *record.categories.entry(round.category.clone()).or_insert(0) += 1;
+                    if round.secret.contains(&i) {
+                        record.times_imposter += 1;
+                        // A Mr. White who steals the round wins it alone
+                        if !imposter_found && stolen_by.is_none_or(|thief| thief == i) {
+                            record.imposter_wins += 1;
+                        }
+                    }
+                }
+            }
+            GameEvent::WordGuessed(_) | GameEvent::RoundAbandoned | GameEvent::PlayerLeft { .. } => {}
+        }
+        follow_seating(&mut ids, event);
+    }
+
+    registry.recorded.insert(session_id.to_string(), end);
+    true
+}
+
+/// Players who voted to evict `evicted`: their ballot in a secret vote, or every civilian
+/// still in when the group agreed on it together
+fn voters_against(round: &RoundInProgress, player_count: usize, evicted: usize) -> Vec<usize> {
+    let secret_vote = round.last_vote.as_ref().filter(|tally| match tally.outcome {
+        VoteOutcome::Evicted(i) | VoteOutcome::RandomEviction { evicted: i, .. } => i == evicted,
+        _ => false,
+    });
+    match secret_vote {
+        Some(tally) => tally.ballots.iter().filter(|(_, &target)| target == evicted).map(|(&voter, _)| voter).collect(),
+        None => (0..player_count)
+            .filter(|i| *i != evicted && !round.secret.contains(i) && !round.evicted.contains(i))
+            .collect(),
+    }
+}
+
+/// Every player seen in this browser
+pub fn load_player_registry() -> PlayerRegistry {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let stored = window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(PLAYER_REGISTRY_KEY).ok().flatten());
+        stored
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        PlayerRegistry::default()
+    }
+}
+
+fn store_player_registry(_registry: &PlayerRegistry) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        if let Some(window) = window() {
+            if let Ok(Some(storage)) = window.local_storage() {
+                if let Ok(json) = serde_json::to_string(_registry) {
+                    let _ = storage.set_item(PLAYER_REGISTRY_KEY, &json);
+                }
+            }
+        }
+    }
+}
+
+/// Fold any newly finished rounds of this game into the stored registry
+pub fn save_player_stats(state: &GameState) {
+    let mut registry = load_player_registry();
+    if record_history(&mut registry, &state.session_id, &state.history) {
+        store_player_registry(&registry);
+    }
+}
+
+/// A duplicated game carries on from the same rounds, so they aren't counted a second time
+pub fn copy_recorded_progress(from_session: &str, to_session: &str) {
+    let mut registry = load_player_registry();
+    if let Some(&recorded) = registry.recorded.get(from_session) {
+        registry.recorded.insert(to_session.to_string(), recorded);
+        store_player_registry(&registry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn started(names: &[&str], ids: &[&str]) -> GameEvent {
+        GameEvent::GameStarted {
+            players: names.iter().map(|n| n.to_string()).collect(),
+            player_ids: ids.iter().map(|n| n.to_string()).collect(),
+            seed: 0,
+        }
+    }
+
+    fn round(category: &str, imposters: Vec<usize>) -> GameEvent {
+        GameEvent::RoundStarted {
+            category_id: category.to_lowercase(),
+            category_name: category.to_string(),
+            category_icon: String::new(),
+            civilian_word: "Coffee".to_string(),
+            imposter_word: "Tea".to_string(),
+            imposters,
+            mr_whites: Vec::new(),
+            scores: vec![0; 4],
+        }
+    }
+
+    fn ended(imposter_found: bool) -> GameEvent {
+        GameEvent::RoundEnded { imposter_found, stolen_by: None, score_changes: vec![0; 4], scores: vec![0; 4] }
+    }
+
+    fn evicted(player_index: usize, card_type: CardType) -> GameEvent {
+        GameEvent::Evicted { player_index, card_type }
+    }
+
+    #[test]
+    fn finished_rounds_are_counted_once() {
+        let mut history = vec![
+            started(&["Ann", "Bob", "Cat", "Dan"], &["a", "b", "c", "d"]),
+            round("Food", vec![3]),
+            evicted(0, CardType::Normal),
+            evicted(3, CardType::Imposter),
+            ended(true),
+            round("Food", vec![1]),
+        ];
+        let mut registry = PlayerRegistry::default();
+        assert!(record_history(&mut registry, "s", &history));
+        // The second round has no result yet, and nothing new is counted twice
+        assert!(!record_history(&mut registry, "s", &history));
+
+        let ann = &registry.players["a"];
+        assert_eq!((ann.games_played, ann.rounds_played, ann.wrongly_evicted), (1, 1, 1));
+        // Ann was already out, so only Bob and Cat voted the imposter out
+        assert_eq!(ann.correct_evictions, 0);
+        assert_eq!(registry.players["b"].correct_evictions, 1);
+        assert_eq!(registry.players["d"].times_imposter, 1);
+        assert_eq!(registry.players["d"].imposter_wins, 0);
+
+        history.push(ended(false));
+        assert!(record_history(&mut registry, "s", &history));
+        let bob = &registry.players["b"];
+        assert_eq!((bob.rounds_played, bob.times_imposter, bob.imposter_wins), (2, 1, 1));
+        assert_eq!(bob.imposter_win_rate(), Some(1.0));
+        assert_eq!(bob.favourite_categories(3), vec!["Food".to_string()]);
+    }
+
+    #[test]
+    fn secret_votes_credit_the_voters() {
+        let tally = VoteTally {
+            round_number: 1,
+            ballots: BTreeMap::from([(0, 3), (1, 3), (2, 0), (3, 0)]),
+            outcome: VoteOutcome::RandomEviction { tied: vec![0, 3], evicted: 3 },
+        };
+        let history = vec![
+            started(&["Ann", "Bob", "Cat", "Dan"], &["a", "b", "c", "d"]),
+            round("Food", vec![3]),
+            GameEvent::VoteCounted(tally),
+            evicted(3, CardType::Imposter),
+            ended(true),
+        ];
+        let mut registry = PlayerRegistry::default();
+        record_history(&mut registry, "s", &history);
+        let credited: Vec<u32> = ["a", "b", "c", "d"].iter().map(|id| registry.players[*id].correct_evictions).collect();
+        assert_eq!(credited, vec![1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn players_who_come_and_go_keep_their_seats_straight() {
+        let history = vec![
+            started(&["Ann", "Bob", "Cat", "Dan"], &["a", "b", "c", "d"]),
+            round("Food", vec![3]),
+            ended(false),
+            GameEvent::PlayerLeft { player_index: 0 },
+            GameEvent::PlayerJoined { name: "Eve".to_string(), id: "e".to_string(), score: 0 },
+        ];
+        let mut registry = PlayerRegistry::default();
+        record_history(&mut registry, "s", &history);
+        assert_eq!(registry.players["e"].games_played, 1);
+
+        // Picked up again later: index 3 is now Eve, not Dan
+        let mut history = history;
+        history.extend([round("Food", vec![3]), ended(false)]);
+        record_history(&mut registry, "s", &history);
+        assert_eq!(registry.players["e"].times_imposter, 1);
+        assert_eq!(registry.players["d"].times_imposter, 1);
+        assert_eq!(registry.players["a"].rounds_played, 1);
+    }
+
+    #[test]
+    fn names_are_matched_loosely() {
+        let mut registry = PlayerRegistry::default();
+        record_history(&mut registry, "s", &[started(&["Ann"], &["a"])]);
+        assert_eq!(registry.find_by_name("  ann ").map(|r| r.id.as_str()), Some("a"));
+        assert!(registry.find_by_name("").is_none());
+    }
+}
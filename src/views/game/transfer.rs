@@ -0,0 +1,291 @@
+use crate::views::game::migrations::{restore_game_value, RestoreError};
+use crate::views::game::rng::entropy_seed;
+use crate::views::game::types::{GameEvent, GameScreen, GameState};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ============================================================================
+// Game transfer - moving a whole session to another device as a file or QR code
+// ============================================================================
+
+/// Marks a payload as a game exported from here. The game inside carries its own save
+/// version, so games exported by older versions are migrated on import like any other save.
+const EXPORT_FORMAT: &str = "ultimate-imposter-game";
+
+/// URL-safe, so codes survive being pasted into links and chat apps
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Serialize, Deserialize)]
+struct GameExport {
+    format: String,
+    game: Value,
+}
+
+/// Cards are dealt and the round hasn't been settled yet
+pub fn round_in_play(state: &GameState) -> bool {
+    !state.cards.is_empty()
+        && !matches!(state.game_screen, GameScreen::RoundEnd { .. } | GameScreen::GameScore | GameScreen::History)
+}
+
+/// The game as it was before the round in play was dealt, with nothing left that gives its words away.
+///
+/// The round goes back to category selection, like restarting it, and is dealt again after importing.
+/// The seed would deal it the same way again, so it's left out and the importing device draws a new one.
+pub fn without_round_in_play(state: &GameState) -> GameState {
+    let mut game = state.clone();
+    if !round_in_play(state) {
+        return game;
+    }
+
+    let secret: Vec<usize> = state.imposter_indices.union(&state.mr_white_indices).copied().collect();
+    // The log's current round names the words and the imposters, and its scores are the ones to go back to
+    if let Some(deal) = game.history.iter().rposition(|e| matches!(e, GameEvent::RoundStarted { .. })) {
+        if let GameEvent::RoundStarted { scores, .. } = &game.history[deal] {
+            for (player, &score) in game.players.iter_mut().zip(scores) {
+                player.score = score;
+            }
+        }
+        game.history.truncate(deal);
+    }
+    // The newest used pair is the one in play, and its ID gives the words away
+    let category_id = state.selected_category_id.clone().unwrap_or_default();
+    let in_play = game.used_pairs.get_mut(&category_id).and_then(|used| used.pop());
+    // Dealing it flipped which way round it goes next time. Flip it back if an earlier round
+    // in the log dealt it too, otherwise forget it as if it had never come up.
+    if let (Some(pair_id), Some((civilian, imposter))) = (in_play, &state.current_round_words) {
+        let dealt_before = game.history.iter().any(|event| {
+            matches!(event, GameEvent::RoundStarted { category_id: dealt_from, civilian_word, imposter_word, .. }
+                if *dealt_from == category_id
+                    && [civilian_word, imposter_word].contains(&civilian)
+                    && [civilian_word, imposter_word].contains(&imposter))
+        });
+        if let Some(orientations) = game.pair_orientation.get_mut(&category_id) {
+            match orientations.get_mut(&pair_id) {
+                Some(as_written) if dealt_before => *as_written = !*as_written,
+                _ => {
+                    orientations.remove(&pair_id);
+                }
+            }
+        }
+    }
+    // Undo the deal's fair deal bookkeeping. Streaks can't be wound back without showing who was dealt what.
+    for (i, player) in game.players.iter_mut().enumerate() {
+        if secret.contains(&i) {
+            player.times_imposter = player.times_imposter.saturating_sub(1);
+        }
+        player.imposter_streak = 0;
+        player.is_eliminated = false;
+    }
+    game.cards.clear();
+    game.imposter_indices.clear();
+    game.mr_white_indices.clear();
+    game.current_round_words = None;
+    game.guesses.clear();
+    game.ballots.clear();
+    game.revote_candidates.clear();
+    game.vote_tallies.clear();
+    game.timer = None;
+    game.hide_seed();
+    game.game_screen = GameScreen::CategorySelection;
+    game
+}
+
+/// The whole session as compact JSON, leaving out the round in play if there is one
+pub fn export_game(state: &GameState) -> serde_json::Result<String> {
+    serde_json::to_string(&GameExport {
+        format: EXPORT_FORMAT.to_string(),
+        game: serde_json::to_value(without_round_in_play(state))?,
+    })
+}
+
+/// The export as a code to paste or show as a QR code
+pub fn export_code(state: &GameState) -> serde_json::Result<String> {
+    export_game(state).map(|json| encode_base64(json.as_bytes()))
+}
+
+/// Read a game exported on another device, as JSON or as a code
+pub fn import_game(payload: &str) -> Result<GameState, RestoreError> {
+    let payload = payload.trim();
+    let json = if payload.starts_with('{') {
+        payload.to_string()
+    } else {
+        decode_base64(payload)
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or(RestoreError::NotAnExport)?
+    };
+    let mut game = match serde_json::from_str::<GameExport>(&json) {
+        Ok(export) if export.format == EXPORT_FORMAT => restore_game_value(export.game)?,
+        _ => return Err(RestoreError::NotAnExport),
+    };
+    // Exported mid-round without its seed, so the rest of the game is dealt from a new one
+    if game.rng_state == 0 && !game.players.is_empty() {
+        game.rng_state = entropy_seed().map_err(RestoreError::Entropy)?;
+    }
+    Ok(game)
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut code = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let block = chunk.iter().enumerate().fold(0u32, |block, (i, &b)| block | u32::from(b) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            code.push(BASE64_ALPHABET[(block >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    code
+}
+
+/// Also takes standard base64, with or without padding, and ignores line breaks
+fn decode_base64(code: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(code.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for c in code.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        bits = bits << 6 | u32::from(value);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::types::{CardType, GameCard, Player, SCHEMA_VERSION};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn player(name: &str, score: i32, times_imposter: u32) -> Player {
+        Player {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            score,
+            is_eliminated: false,
+            times_imposter,
+            imposter_streak: 1,
+        }
+    }
+
+    fn mid_round() -> GameState {
+        let mut state = GameState::new("abc".to_string());
+        state.players = vec![player("Ann", 4, 1), player("Bob", 2, 2), player("Cat", 3, 1)];
+        state.players[1].is_eliminated = true;
+        state.cards = vec![
+            GameCard { card_type: CardType::Normal, word: "Coffee".to_string() },
+            GameCard { card_type: CardType::Imposter, word: "Tea".to_string() },
+            GameCard { card_type: CardType::Normal, word: "Coffee".to_string() },
+        ];
+        state.imposter_indices = BTreeSet::from([1]);
+        state.current_round_words = Some(("Coffee".to_string(), "Tea".to_string()));
+        state.selected_category_id = Some("builtin/food-drinks".to_string());
+        state.used_pairs.insert("builtin/food-drinks".to_string(), vec!["p1".to_string(), "p2".to_string()]);
+        state.pair_orientation.insert(
+            "builtin/food-drinks".to_string(),
+            BTreeMap::from([("p1".to_string(), true), ("p2".to_string(), false)]),
+        );
+        state.seed = Some(7);
+        state.game_seed = 7;
+        state.rng_state = 99;
+        state.history = vec![
+            GameEvent::GameStarted { players: Vec::new(), player_ids: Vec::new(), seed: 7 },
+            GameEvent::RoundAbandoned,
+            GameEvent::RoundStarted {
+                category_id: "builtin/food-drinks".to_string(),
+                category_name: "Food".to_string(),
+                category_icon: String::new(),
+                civilian_word: "Coffee".to_string(),
+                imposter_word: "Tea".to_string(),
+                imposters: vec![1],
+                mr_whites: Vec::new(),
+                scores: vec![1, 2, 3],
+            },
+        ];
+        state.game_screen = GameScreen::Voting;
+        state
+    }
+
+    #[test]
+    fn base64_round_trips() {
+        for text in ["", "a", "ab", "abc", "abcd", "{\"ü\":1}"] {
+            let code = encode_base64(text.as_bytes());
+            assert_eq!(decode_base64(&code).unwrap(), text.as_bytes());
+        }
+        assert_eq!(encode_base64(b"\xfb\xff"), "-_8");
+        assert_eq!(decode_base64("+/8=\n").unwrap(), b"\xfb\xff");
+        assert!(decode_base64("not base64!").is_none());
+    }
+
+    #[test]
+    fn finished_games_come_back_exactly() {
+        let mut state = mid_round();
+        state.game_screen = GameScreen::GameScore;
+        for payload in [export_game(&state).unwrap(), export_code(&state).unwrap()] {
+            let imported = import_game(&payload).unwrap();
+            assert_eq!(serde_json::to_value(imported).unwrap(), serde_json::to_value(&state).unwrap());
+        }
+    }
+
+    #[test]
+    fn the_round_in_play_is_left_out() {
+        let state = mid_round();
+        let json = export_game(&state).unwrap();
+        assert!(!json.contains("Coffee") && !json.contains("Tea") && !json.contains("p2"));
+
+        let imported = import_game(&export_code(&state).unwrap()).unwrap();
+        assert_eq!(imported.game_screen, GameScreen::CategorySelection);
+        assert!(imported.cards.is_empty() && imported.imposter_indices.is_empty());
+        assert_eq!(
+            imported.history,
+            vec![GameEvent::GameStarted { players: Vec::new(), player_ids: Vec::new(), seed: 0 }, GameEvent::RoundAbandoned]
+        );
+        assert_eq!(imported.used_pairs["builtin/food-drinks"], vec!["p1".to_string()]);
+        assert_eq!(imported.pair_orientation["builtin/food-drinks"], BTreeMap::from([("p1".to_string(), true)]));
+        // The seed would deal the round the same way again, so the import draws a new one
+        assert_eq!((imported.seed, imported.game_seed), (None, 0));
+        assert!(![0, 99].contains(&imported.rng_state));
+        let players: Vec<(i32, u32, u32, bool)> =
+            imported.players.iter().map(|p| (p.score, p.times_imposter, p.imposter_streak, p.is_eliminated)).collect();
+        assert_eq!(players, vec![(1, 1, 0, false), (2, 1, 0, false), (3, 1, 0, false)]);
+        assert_eq!(imported.session_id, "abc");
+
+        // A pair that came up before goes back to the way round it was due to be dealt
+        let mut state = mid_round();
+        state.history.insert(
+            1,
+            GameEvent::RoundStarted {
+                category_id: "builtin/food-drinks".to_string(),
+                category_name: "Food".to_string(),
+                category_icon: String::new(),
+                civilian_word: "Tea".to_string(),
+                imposter_word: "Coffee".to_string(),
+                imposters: vec![0],
+                mr_whites: Vec::new(),
+                scores: vec![0, 0, 0],
+            },
+        );
+        assert!(without_round_in_play(&state).pair_orientation["builtin/food-drinks"]["p2"]);
+    }
+
+    #[test]
+    fn other_payloads_are_refused() {
+        assert_eq!(import_game("{\"session_id\":\"abc\"}").unwrap_err(), RestoreError::NotAnExport);
+        assert_eq!(import_game("hello there").unwrap_err(), RestoreError::NotAnExport);
+        let newer = format!(
+            "{{\"format\":\"{}\",\"game\":{{\"version\":{}}}}}",
+            EXPORT_FORMAT,
+            SCHEMA_VERSION + 1
+        );
+        assert_eq!(import_game(&newer).unwrap_err(), RestoreError::TooNew(SCHEMA_VERSION + 1));
+        let damaged = format!("{{\"format\":\"{}\",\"game\":{{}}}}", EXPORT_FORMAT);
+        assert!(matches!(import_game(&damaged), Err(RestoreError::Unreadable(_))));
+    }
+}
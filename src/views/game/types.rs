@@ -1,16 +1,24 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Player {
+    #[serde(default)]
+    pub id: String, // Stable ID linking the player to their lifetime stats
     pub name: String,
     pub score: i32,
     pub is_eliminated: bool,
+    #[serde(default)]
+    pub times_imposter: u32, // Rounds dealt as an imposter or Mr. White this game, for fair deal
+    #[serde(default)]
+    pub imposter_streak: u32, // Deals in a row this player has been an imposter or Mr. White
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum CardType {
     Normal,
     Imposter,
+    MrWhite, // Dealt no word at all
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -26,38 +34,459 @@ pub enum GameScreen {
     CategoryReveal { category_name: String, category_icon: String },
     CardView { current_player_index: usize },
     Voting,
-    Elimination { eliminated_index: usize, was_imposter: bool },
-    RoundEnd { imposter_found: bool, game_over: bool },
+    Ballot, // Secret vote: everyone still in picks someone to evict
+    Elimination {
+        eliminated_index: usize,
+        was_imposter: bool,
+        #[serde(default)]
+        was_mr_white: bool,
+    },
+    MrWhiteGuess { player_index: usize }, // Evicted Mr. White's one chance to name the civilian word
+    ImposterGuess { player_index: usize }, // Last chance rule: evicted imposter names the civilian word
+    RoundEnd {
+        imposter_found: bool,
+        game_over: bool,
+        #[serde(default)]
+        stolen_by: Option<usize>, // Player who stole the win with a correct guess
+    },
     GameScore,
+    History, // Walk back through the rounds played this session
 }
 
+/// How close an evicted player's guess came to the civilian word
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GuessMatch {
+    Exact, // The word itself or one of its synonyms
+    Close, // A typo or two away
+    Miss,
+}
+
+/// A guess at the civilian word made this round
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct WordGuess {
+    pub player_index: usize,
+    pub guess: String,
+    pub result: GuessMatch,
+}
+
+/// Something that happened this session, kept in order in `GameState::history`.
+/// Player indices refer to the players of the most recent `GameStarted` before them,
+/// with any `PlayerJoined` added on the end and any `PlayerLeft` taken out.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GameEvent {
+    GameStarted {
+        players: Vec<String>,
+        #[serde(default)]
+        player_ids: Vec<String>, // Stable IDs, in the same order as `players`
+        seed: u64,
+    },
+    RoundStarted {
+        category_id: String,
+        category_name: String,
+        category_icon: String,
+        civilian_word: String,
+        imposter_word: String,
+        imposters: Vec<usize>,
+        mr_whites: Vec<usize>,
+        scores: Vec<i32>, // Totals going into the round
+    },
+    VoteCounted(VoteTally),
+    Evicted { player_index: usize, card_type: CardType },
+    WordGuessed(WordGuess),
+    RoundEnded {
+        imposter_found: bool,
+        stolen_by: Option<usize>,
+        score_changes: Vec<i32>, // Points each player gained (or lost) over the round
+        scores: Vec<i32>, // Totals once the round was settled
+    },
+    RoundAbandoned, // Restarted before anyone won
+    PlayerJoined {
+        name: String,
+        #[serde(default)]
+        id: String,
+        score: i32, // What they started on
+    },
+    PlayerLeft { player_index: usize },
+}
+
+/// What a player joining part way through a game starts on
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum StartingScore {
+    #[default]
+    Zero,
+    Average, // Everyone else's average, rounded
+}
+
+/// Lifetime stats for one player, gathered across every game played in this browser
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerRecord {
+    pub id: String,
+    pub name: String, // The name they last played under
+    pub games_played: u32,
+    pub rounds_played: u32, // Rounds that were played to a result
+    pub times_imposter: u32, // Of those, rounds as an imposter or Mr. White
+    pub imposter_wins: u32,
+    pub correct_evictions: u32, // Imposters and Mr. Whites evicted with their vote
+    pub wrongly_evicted: u32, // Times voted out as a civilian
+    pub categories: BTreeMap<String, u32>, // Category name -> rounds played in it
+}
+
+/// Every player ever seen, and how much of each session's history has been counted
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerRegistry {
+    pub players: BTreeMap<String, PlayerRecord>, // Player ID -> record
+    pub recorded: BTreeMap<String, usize>, // Session ID -> history events already counted
+}
+
+/// A saved group of players, to fill in the setup screen in one go
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Roster {
+    pub name: String, // e.g. "Friday crew"
+    pub players: Vec<RosterPlayer>, // In seating order
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RosterPlayer {
+    pub name: String,
+    #[serde(default)]
+    pub id: String, // Their stats ID, empty if they hadn't played yet when the group was saved
+}
+
+/// What happens when a secret vote ends in a tie
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum TieBreak {
+    #[default]
+    Revote, // Vote again between the tied players; a second tie evicts nobody
+    NoEviction, // Nobody leaves and the discussion carries on
+    Random, // One of the tied players, drawn from the game's seed
+}
+
+/// How a counted vote turned out
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum VoteOutcome {
+    Evicted(usize),
+    Revote { tied: Vec<usize> },
+    NoEviction { tied: Vec<usize> },
+    RandomEviction { tied: Vec<usize>, evicted: usize },
+}
+
+/// One counted secret vote
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct VoteTally {
+    pub round_number: i32,
+    pub ballots: BTreeMap<usize, usize>, // Voter -> who they voted to evict
+    pub outcome: VoteOutcome,
+}
+
+/// Points for each way a round can go, chosen at setup
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringRules {
+    pub civilian_win: i32, // Each civilian when every imposter and Mr. White is out
+    pub imposter_survival: i32, // Each imposter or Mr. White once civilians can't outvote them
+    pub survival_bonus: i32, // Each imposter or Mr. White, for every vote they survive
+    pub civilian_eviction_penalty: i32, // Taken from each remaining civilian when they evict one of their own
+    pub mr_white_guess: i32, // Evicted Mr. White who names the civilian word
+    pub last_chance: i32, // Evicted imposter who names the civilian word
+    pub last_chance_close: i32, // Evicted imposter whose guess is a typo or two off
+    pub whole_team_scores: bool, // Evicted players still score when their side wins
+    pub target_score: Option<i32>, // First to reach this wins the match; None plays on forever
+}
+
+/// Named starting points for `ScoringRules`
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ScoringPreset {
+    Classic,
+    Undercover,
+    Team,
+}
+
+impl ScoringPreset {
+    pub const ALL: [ScoringPreset; 3] = [ScoringPreset::Classic, ScoringPreset::Undercover, ScoringPreset::Team];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScoringPreset::Classic => "Classic",
+            ScoringPreset::Undercover => "Undercover",
+            ScoringPreset::Team => "Team",
+        }
+    }
+
+    pub fn rules(self) -> ScoringRules {
+        match self {
+            // The original scoring: big reward for the imposter, nothing for surviving a vote
+            ScoringPreset::Classic => ScoringRules {
+                civilian_win: 10,
+                imposter_survival: 20,
+                survival_bonus: 0,
+                civilian_eviction_penalty: 0,
+                mr_white_guess: 20,
+                last_chance: 10,
+                last_chance_close: 5,
+                whole_team_scores: false,
+                target_score: None,
+            },
+            // Small numbers in the style of the Undercover party game, played to 30
+            ScoringPreset::Undercover => ScoringRules {
+                civilian_win: 2,
+                imposter_survival: 10,
+                survival_bonus: 1,
+                civilian_eviction_penalty: 0,
+                mr_white_guess: 6,
+                last_chance: 3,
+                last_chance_close: 1,
+                whole_team_scores: false,
+                target_score: Some(30),
+            },
+            // The whole winning side scores, and evicting a teammate costs everyone
+            ScoringPreset::Team => ScoringRules {
+                civilian_win: 5,
+                imposter_survival: 10,
+                survival_bonus: 2,
+                civilian_eviction_penalty: 2,
+                mr_white_guess: 10,
+                last_chance: 5,
+                last_chance_close: 2,
+                whole_team_scores: true,
+                target_score: Some(50),
+            },
+        }
+    }
+
+    /// The preset these rules came from, or `None` once they've been edited
+    pub fn matching(rules: &ScoringRules) -> Option<ScoringPreset> {
+        ScoringPreset::ALL.into_iter().find(|preset| preset.rules() == *rules)
+    }
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        ScoringPreset::Classic.rules()
+    }
+}
+
+/// Countdown lengths in seconds, chosen at setup. 0 leaves that phase untimed.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerSettings {
+    pub card_secs: u32, // Each player's look at their card
+    pub discussion_secs: u32, // Talking it over on the voting screen
+    pub voting_secs: u32, // A secret ballot, including each revote
+    pub sound: bool, // Beep for the last few seconds and when time runs out
+}
+
+/// The parts of a round that can run against the clock
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TimedPhase {
+    CardReveal,
+    Discussion,
+    Voting,
+}
+
+/// Countdown for the phase on screen.
+///
+/// Only the deadline is stored, never a running count, so a reload picks up where the clock
+/// really is. Times are milliseconds since the Unix epoch, read from the device driving the game.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PhaseTimer {
+    pub phase: TimedPhase,
+    pub duration_ms: i64,
+    pub remaining_ms: i64, // Time left when the clock was last stopped
+    pub deadline_ms: Option<i64>, // When it runs out; None until started and while paused
+    pub paused: bool,
+}
+
+impl PhaseTimer {
+    /// A full countdown, waiting for the screen to start it
+    pub fn new(phase: TimedPhase, secs: u32) -> Self {
+        let duration_ms = i64::from(secs) * 1000;
+        PhaseTimer { phase, duration_ms, remaining_ms: duration_ms, deadline_ms: None, paused: false }
+    }
+
+    /// Milliseconds left at `now_ms`
+    pub fn remaining_at(&self, now_ms: i64) -> i64 {
+        match self.deadline_ms {
+            Some(deadline) => (deadline - now_ms).max(0),
+            None => self.remaining_ms,
+        }
+    }
+}
+
+/// Player cap for new games and saves from before it was configurable
+pub const DEFAULT_PLAYER_CAP: usize = 10;
+
+/// Layout of `GameState` this version saves; older saves are migrated up to it (see `migrations.rs`)
+pub const SCHEMA_VERSION: u32 = 2;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameState {
+    #[serde(default)]
+    pub version: u32, // Layout this was saved in, 0 for saves from before versioning
     pub session_id: String,
+    #[serde(default)]
+    pub save_name: String, // Name given in the saved games list, empty to go by the players
+    #[serde(default)]
+    pub last_played_ms: i64, // When this session was last saved, 0 if never
     pub game_screen: GameScreen,
     pub players: Vec<Player>,
     pub player_count_input: String,
+    #[serde(default = "default_player_cap")]
+    pub player_cap: usize, // Most players the setup screen allows
     pub player_names: Vec<String>,
+    #[serde(default)]
+    pub player_ids: Vec<String>, // Stable ID for each setup slot, empty until linked or the game starts
     pub round_number: i32,
     pub cards: Vec<GameCard>,
-    pub imposter_index: usize,
+    pub imposter_indices: BTreeSet<usize>, // Seats holding an imposter card this round
+    #[serde(default)]
+    pub imposter_count: Option<usize>, // Imposters per round; None scales with the player count
+    #[serde(default)]
+    pub mr_white_count: usize, // Players dealt no word each round
+    #[serde(default)]
+    pub mr_white_indices: BTreeSet<usize>, // Seats holding a Mr. White card this round
     pub current_category: Option<(String, String)>, // (name, icon)
-    pub selected_category_index: Option<usize>, // Selected category index for the round
+    #[serde(default)]
+    pub selected_category_id: Option<String>, // Stable ID of the category picked for the round
     pub hide_imposter_identity: bool, // Toggle to hide imposter from themselves
     pub current_round_words: Option<(String, String)>, // (civilian_word, imposter_word)
     pub starting_player_index: usize, // Rotates each round to determine who picks card first
+    #[serde(default)]
+    pub disabled_packs: Vec<String>, // Word pack IDs switched off for this game
+    #[serde(default)]
+    pub seed: Option<u64>, // Seed chosen by the host; None draws a fresh one each game
+    #[serde(default)]
+    pub game_seed: u64, // Seed the current game was dealt from, shown on the score screen
+    #[serde(default)]
+    pub rng_state: u64, // How far the game has drawn from its seeded stream
+    #[serde(default)]
+    pub last_game_seed: Option<u64>, // Seed of the game before this one, shown once it's over
+    #[serde(default)]
+    pub fair_deal: bool, // Favour players who have been the imposter least
+    #[serde(default)]
+    pub imposter_last_chance: bool, // Evicted imposters get one guess at the civilian word
+    #[serde(default)]
+    pub ballot_mode: bool, // Everyone votes in secret instead of one tap for the group
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    #[serde(default)]
+    pub ballots: BTreeMap<usize, Option<usize>>, // Voter -> choice in the vote under way; None in room views until counted
+    #[serde(default)]
+    pub revote_candidates: Vec<usize>, // Tied players a revote is limited to, empty for everyone
+    #[serde(default)]
+    pub vote_tallies: Vec<VoteTally>, // Counted votes this round, oldest first
+    #[serde(default)]
+    pub scoring: ScoringRules, // Points for this game, classic for saves from before scoring was configurable
+    #[serde(default)]
+    pub guesses: Vec<WordGuess>, // Guesses at the civilian word this round, in order
+    #[serde(default)]
+    pub history: Vec<GameEvent>, // Everything that happened this session, oldest first; only ever appended to
+    #[serde(default)]
+    pub timers: TimerSettings,
+    #[serde(default)]
+    pub timer: Option<PhaseTimer>, // Countdown for the current phase, if it's timed
+    #[serde(default)]
+    pub max_imposter_streak: u32, // Fair deal: most rounds in a row as imposter, 0 for no cap
+    #[serde(default)]
+    pub used_pairs: BTreeMap<String, Vec<String>>, // Category ID -> pair IDs dealt this session, oldest first
+    #[serde(default)]
+    pub pair_orientation: BTreeMap<String, BTreeMap<String, bool>>, // Category ID -> pair ID -> deal it as written next time
+}
+
+fn default_player_cap() -> usize {
+    DEFAULT_PLAYER_CAP
+}
+
+impl GameState {
+    /// Fresh state for a new session, sitting on the setup screen
+    pub fn new(session_id: String) -> Self {
+        GameState {
+            version: SCHEMA_VERSION,
+            session_id,
+            save_name: String::new(),
+            last_played_ms: 0,
+            game_screen: GameScreen::Setup,
+            players: Vec::new(),
+            player_count_input: String::from("3"),
+            player_cap: default_player_cap(),
+            player_names: Vec::new(),
+            player_ids: Vec::new(),
+            round_number: 1,
+            cards: Vec::new(),
+            imposter_indices: BTreeSet::new(),
+            imposter_count: None,
+            mr_white_count: 0,
+            mr_white_indices: BTreeSet::new(),
+            current_category: None,
+            selected_category_id: None,
+            hide_imposter_identity: false,
+            current_round_words: None,
+            starting_player_index: 0,
+            disabled_packs: Vec::new(),
+            seed: None,
+            game_seed: 0,
+            rng_state: 0,
+            last_game_seed: None,
+            fair_deal: false,
+            imposter_last_chance: false,
+            ballot_mode: false,
+            tie_break: TieBreak::Revote,
+            ballots: BTreeMap::new(),
+            revote_candidates: Vec::new(),
+            vote_tallies: Vec::new(),
+            scoring: ScoringRules::default(),
+            guesses: Vec::new(),
+            history: Vec::new(),
+            timers: TimerSettings::default(),
+            timer: None,
+            max_imposter_streak: 0,
+            used_pairs: BTreeMap::new(),
+            pair_orientation: BTreeMap::new(),
+        }
+    }
+
+    /// Forget the seed and how far the game has drawn from it, which would replay the deals
+    /// still to come, along with the seed logged when the game started
+    pub fn hide_seed(&mut self) {
+        self.seed = None;
+        self.game_seed = 0;
+        self.rng_state = 0;
+        if let Some(GameEvent::GameStarted { seed, .. }) =
+            self.history.iter_mut().rev().find(|e| matches!(e, GameEvent::GameStarted { .. }))
+        {
+            *seed = 0;
+        }
+    }
 }
 
 // Word list structures
-#[derive(Debug, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct WordCategory {
+    /// Namespaced as `{pack_id}/{slug}` when the pack is loaded. The slug comes from an
+    /// explicit `id` in the file if there is one, otherwise from the category name.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// The pack this category came from, filled in when the pack is loaded
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pack_id: String,
     pub name: String,
     pub icon: String,
     pub pairs: Vec<(String, String)>,
+    /// Other answers accepted for a word when an evicted player guesses it
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub synonyms: BTreeMap<String, Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct WordList {
     pub categories: Vec<WordCategory>,
 }
 
+/// A set of categories loaded on top of the built-in `words.yaml`
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct WordPack {
+    pub id: String,
+    pub name: String,
+    pub categories: Vec<WordCategory>,
+}
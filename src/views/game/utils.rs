@@ -1,63 +1,110 @@
-use crate::views::game::types::{GameCard, CardType, WordList, WordCategory};
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use crate::views::game::rng::{coin_flip, uniform_index, weighted_index, GameRng};
+use crate::views::game::types::{GameCard, CardType, WordList, WordCategory, WordPack};
+use crate::views::game::word_packs::{builtin_pack, extra_word_packs};
+use std::collections::BTreeMap;
 
 // Include the YAML file at compile time
 const WORDS_YAML: &str = include_str!("../../../words.yaml");
 
-/// Generate a random starting index using getrandom
+/// Generate a random starting index
 /// Used for randomizing which player goes first
-pub fn get_random_starting_index(max: usize) -> usize {
+pub fn get_random_starting_index(rng: &mut dyn GameRng, max: usize) -> usize {
     if max == 0 {
         return 0;
     }
-    let mut buf = [0u8; 4];
-    getrandom::getrandom(&mut buf).unwrap_or_default();
-    let num = u32::from_le_bytes(buf);
-    (num as usize) % max
+    uniform_index(rng, max)
 }
 
-/// Load word categories from YAML
+/// Load word categories from YAML, skipping any without pairs.
+/// `words.yaml` is built in and checked by the tests, so one that doesn't parse is a bug, not bad input.
 fn load_word_categories() -> WordList {
-    serde_yaml::from_str(WORDS_YAML).expect("Failed to parse words.yaml")
+    let mut word_list: WordList = serde_yaml::from_str(WORDS_YAML)
+        .unwrap_or_else(|e| panic!("the built-in words.yaml doesn't parse: {}", e));
+    word_list.categories.retain(|c| !c.pairs.is_empty());
+    word_list
 }
 
-/// Get all available categories for selection
+/// The built-in word list followed by every word pack available on this device
+pub fn get_all_word_packs() -> Vec<WordPack> {
+    let mut packs = vec![builtin_pack(load_word_categories())];
+    packs.extend(extra_word_packs());
+    packs
+}
+
+/// Get all available categories for selection, in pack order
 pub fn get_all_categories() -> Vec<WordCategory> {
-    load_word_categories().categories
+    get_all_word_packs().into_iter().flat_map(|pack| pack.categories).collect()
 }
 
-/// Helper function to generate cards for the round with a specific category
-pub fn generate_cards_for_category(player_count: usize, category_index: usize) -> (Vec<GameCard>, usize, String, String) {
-    use getrandom::getrandom;
-    
-    let word_list = load_word_categories();
-    
-    // Use the selected category
-    let category = &word_list.categories[category_index % word_list.categories.len()];
-    
-    // Get random bytes for word pair selection
-    let mut buf_word = [0u8; 8];
-    let _ = getrandom(&mut buf_word);
-    let random_word = u64::from_le_bytes(buf_word);
-    
-    // Get SEPARATE random bytes for imposter selection (ensures true randomness)
-    let mut buf_imposter = [0u8; 8];
-    let _ = getrandom(&mut buf_imposter);
-    let random_imposter = u64::from_le_bytes(buf_imposter);
-    
-    // Select random word pair from the chosen category
-    let pair_index = (random_word as usize) % category.pairs.len();
+/// Look up a category by its stable ID
+pub fn find_category(category_id: &str) -> Option<WordCategory> {
+    get_all_categories().into_iter().find(|c| c.id == category_id)
+}
+
+/// Look up a category by display name (case-insensitive), for saves made before categories had IDs
+pub fn find_category_by_name(name: &str) -> Option<WordCategory> {
+    get_all_categories().into_iter().find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Stable ID of a word pair, derived from its words so it survives pairs being added or reordered.
+/// FNV-1a rather than `DefaultHasher`, whose output may change between Rust releases.
+pub fn pair_id(pair: &(String, String)) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in pair.0.trim().to_lowercase().bytes().chain([0]).chain(pair.1.trim().to_lowercase().bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// How many players get each secret role in a round
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RoleCounts {
+    pub imposters: usize,
+    pub mr_whites: usize,
+}
+
+/// Cards for one round, and which pair they came from
+pub struct DealtCards {
+    pub cards: Vec<GameCard>,
+    /// Seats dealt the imposter word, in seat order
+    pub imposter_indices: Vec<usize>,
+    /// Seats dealt no word, in seat order
+    pub mr_white_indices: Vec<usize>,
+    pub pair_id: String,
+}
+
+/// Helper function to generate cards for the round with a specific category.
+/// `imposter_weights` (one per player) skews who gets a secret role; `None` picks uniformly.
+/// Pairs in `used_pair_ids` are skipped until every pair in the category has been used, and
+/// then only the last one played is, so the category doesn't start over with a repeat.
+/// `orientations` (pair ID -> which way round to deal it next) is updated so repeats swap roles.
+pub fn generate_cards_for_category(
+    rng: &mut dyn GameRng,
+    orientations: &mut BTreeMap<String, bool>,
+    player_count: usize,
+    roles: RoleCounts,
+    imposter_weights: Option<&[u64]>,
+    used_pair_ids: &[String],
+    category: &WordCategory,
+) -> DealtCards {
+    // Select random word pair from the ones not played yet, or from all but the last one once they run out
+    let mut unused: Vec<usize> = (0..category.pairs.len())
+        .filter(|&i| !used_pair_ids.contains(&pair_id(&category.pairs[i])))
+        .collect();
+    if unused.is_empty() {
+        unused = (0..category.pairs.len())
+            .filter(|&i| category.pairs.len() == 1 || used_pair_ids.last() != Some(&pair_id(&category.pairs[i])))
+            .collect();
+    }
+    let pair_index = unused[uniform_index(rng, unused.len())];
 
     // Track orientation per pair so repeats swap roles each time
-    static PAIR_ORIENTATION: Lazy<Mutex<HashMap<(usize, usize), bool>>> = Lazy::new(|| Mutex::new(HashMap::new()));
-    let mut orientations = PAIR_ORIENTATION.lock().unwrap_or_else(|e| e.into_inner());
     let flip = orientations
-        .entry((category_index, pair_index))
+        .entry(pair_id(&category.pairs[pair_index]))
         .or_insert_with(|| {
             // Initial orientation randomized
-            (random_word & 1) == 1
+            coin_flip(rng)
         });
     let flip_val = *flip;
     // Invert for next time this pair is used
@@ -69,16 +116,37 @@ pub fn generate_cards_for_category(player_count: usize, category_index: usize) -
         (&category.pairs[pair_index].1, &category.pairs[pair_index].0)
     };
     
-    // Select random imposter index (using separate random value)
-    let imposter_idx = (random_imposter as usize) % player_count;
+    // Select the secret roles one at a time, each from the players not picked yet.
+    // Picks come out in random order, so the first ones become Mr. White.
+    let mut candidates: Vec<usize> = (0..player_count).collect();
+    let mut picked = Vec::new();
+    while picked.len() < roles.imposters + roles.mr_whites && !candidates.is_empty() {
+        let pick = match imposter_weights {
+            Some(weights) if weights.len() == player_count => {
+                let remaining: Vec<u64> = candidates.iter().map(|&i| weights[i]).collect();
+                weighted_index(rng, &remaining)
+            }
+            _ => uniform_index(rng, candidates.len()),
+        };
+        picked.push(candidates.remove(pick));
+    }
+    let mut imposter_indices = picked.split_off(roles.mr_whites.min(picked.len()));
+    let mut mr_white_indices = picked;
+    imposter_indices.sort_unstable();
+    mr_white_indices.sort_unstable();
     
     let mut cards = Vec::new();
     for i in 0..player_count {
-        if i == imposter_idx {
+        if imposter_indices.contains(&i) {
             cards.push(GameCard {
                 card_type: CardType::Imposter,
                 word: imposter_word.clone(),
             });
+        } else if mr_white_indices.contains(&i) {
+            cards.push(GameCard {
+                card_type: CardType::MrWhite,
+                word: String::new(),
+            });
         } else {
             cards.push(GameCard {
                 card_type: CardType::Normal,
@@ -87,5 +155,159 @@ pub fn generate_cards_for_category(player_count: usize, category_index: usize) -
         }
     }
     
-    (cards, imposter_idx, category.name.clone(), category.icon.clone())
+    DealtCards {
+        cards,
+        imposter_indices,
+        mr_white_indices,
+        pair_id: pair_id(&category.pairs[pair_index]),
+    }
+}
+
+/// How many pairs in the category haven't been played yet
+pub fn remaining_pair_count(category: &WordCategory, used_pair_ids: &[String]) -> usize {
+    category
+        .pairs
+        .iter()
+        .filter(|pair| !used_pair_ids.contains(&pair_id(pair)))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::rng::SeededRng;
+    use std::collections::HashSet;
+
+    const ONE_IMPOSTER: RoleCounts = RoleCounts { imposters: 1, mr_whites: 0 };
+
+    #[test]
+    fn imposter_is_uniform_across_players() {
+        const PLAYERS: usize = 7;
+        const ROUNDS: usize = 70_000;
+
+        let category = get_all_categories().remove(0);
+        let mut rng = SeededRng::new(0x5eed);
+        let mut counts = [0usize; PLAYERS];
+        for _ in 0..ROUNDS {
+            let dealt = generate_cards_for_category(&mut rng, &mut BTreeMap::new(), PLAYERS, ONE_IMPOSTER, None, &[], &category);
+            let imposter = dealt.imposter_indices[0];
+            assert_eq!(dealt.cards[imposter].card_type, CardType::Imposter);
+            counts[imposter] += 1;
+        }
+
+        // Pearson's chi-squared against a uniform split. 22.46 is the 99.9th percentile
+        // for 6 degrees of freedom, so a fair deal fails this about once in a thousand seeds.
+        let expected = (ROUNDS / PLAYERS) as f64;
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&observed| (observed as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi_squared < 22.46, "imposter counts {:?} aren't uniform (chi² = {:.2})", counts, chi_squared);
+    }
+
+    #[test]
+    fn deals_the_requested_number_of_distinct_imposters() {
+        let category = get_all_categories().remove(0);
+        let mut rng = SeededRng::new(3);
+        for weights in [None, Some(&[0, 0, 5, 1, 1, 0, 0, 0][..])] {
+            for _ in 0..200 {
+                let dealt = generate_cards_for_category(&mut rng, &mut BTreeMap::new(), 8, RoleCounts { imposters: 3, mr_whites: 0 }, weights, &[], &category);
+                assert_eq!(dealt.imposter_indices.len(), 3);
+                assert!(dealt.imposter_indices.windows(2).all(|w| w[0] < w[1]));
+                let imposter_cards = dealt.cards.iter().filter(|c| c.card_type == CardType::Imposter).count();
+                assert_eq!(imposter_cards, 3);
+            }
+        }
+    }
+
+    #[test]
+    fn mr_white_gets_no_word() {
+        let category = get_all_categories().remove(0);
+        let mut rng = SeededRng::new(5);
+        for roles in [RoleCounts { imposters: 1, mr_whites: 1 }, RoleCounts { imposters: 0, mr_whites: 2 }] {
+            let dealt = generate_cards_for_category(&mut rng, &mut BTreeMap::new(), 6, roles, None, &[], &category);
+            assert_eq!(dealt.imposter_indices.len(), roles.imposters);
+            assert_eq!(dealt.mr_white_indices.len(), roles.mr_whites);
+            assert!(dealt.mr_white_indices.iter().all(|i| !dealt.imposter_indices.contains(i)));
+            for (i, card) in dealt.cards.iter().enumerate() {
+                assert_eq!(card.card_type == CardType::MrWhite, dealt.mr_white_indices.contains(&i));
+                assert_eq!(card.word.is_empty(), card.card_type == CardType::MrWhite);
+            }
+        }
+    }
+
+    #[test]
+    fn pairs_do_not_repeat_until_category_runs_out() {
+        let category = get_all_categories().remove(0);
+        let mut rng = SeededRng::new(2);
+        let mut orientations = BTreeMap::new();
+        let mut used = Vec::new();
+        for _ in 0..category.pairs.len() {
+            let dealt = generate_cards_for_category(&mut rng, &mut orientations, 3, ONE_IMPOSTER, None, &used, &category);
+            assert!(!used.contains(&dealt.pair_id));
+            used.push(dealt.pair_id);
+        }
+        assert_eq!(remaining_pair_count(&category, &used), 0);
+
+        // Every pair has been played, so the whole category is back in the pool
+        let dealt = generate_cards_for_category(&mut rng, &mut orientations, 3, ONE_IMPOSTER, None, &used, &category);
+        assert!(used.contains(&dealt.pair_id));
+    }
+
+    #[test]
+    fn starting_over_never_repeats_the_last_pair() {
+        let mut category = get_all_categories().remove(0);
+        category.pairs.truncate(2);
+        let mut orientations = BTreeMap::new();
+        for seed in 0..20 {
+            let mut rng = SeededRng::new(seed);
+            let mut used = Vec::new();
+            for _ in 0..2 {
+                let dealt = generate_cards_for_category(&mut rng, &mut orientations, 3, ONE_IMPOSTER, None, &used, &category);
+                used.push(dealt.pair_id);
+            }
+            let dealt = generate_cards_for_category(&mut rng, &mut orientations, 3, ONE_IMPOSTER, None, &used, &category);
+            assert_eq!(&dealt.pair_id, &used[0], "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn repeated_pair_swaps_roles() {
+        let mut category = get_all_categories().remove(0);
+        category.pairs.truncate(1);
+        let (first, second) = category.pairs[0].clone();
+        let mut rng = SeededRng::new(4);
+        let mut orientations = BTreeMap::new();
+
+        let civilian_word = |dealt: &DealtCards| {
+            dealt.cards.iter().find(|c| c.card_type == CardType::Normal).unwrap().word.clone()
+        };
+        let a = civilian_word(&generate_cards_for_category(&mut rng, &mut orientations, 3, ONE_IMPOSTER, None, &[], &category));
+        let b = civilian_word(&generate_cards_for_category(&mut rng, &mut orientations, 3, ONE_IMPOSTER, None, &[], &category));
+        assert_ne!(a, b);
+        assert!([&first, &second].contains(&&a) && [&first, &second].contains(&&b));
+    }
+
+    #[test]
+    fn bundled_words_parse_with_unique_ids() {
+        let pack = builtin_pack(load_word_categories());
+        assert!(!pack.categories.is_empty());
+        let mut category_ids = HashSet::new();
+        for category in &pack.categories {
+            assert!(category_ids.insert(&category.id), "category ID {} is used twice", category.id);
+            let mut pair_ids = HashSet::new();
+            for pair in &category.pairs {
+                assert!(pair_ids.insert(pair_id(pair)), "{:?} is in {} twice", pair, category.id);
+            }
+        }
+    }
+
+    #[test]
+    fn starting_player_is_in_range() {
+        let mut rng = SeededRng::new(1);
+        assert_eq!(get_random_starting_index(&mut rng, 0), 0);
+        for _ in 0..1_000 {
+            assert!(get_random_starting_index(&mut rng, 5) < 5);
+        }
+    }
 }
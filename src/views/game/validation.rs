@@ -0,0 +1,357 @@
+use super::types::{WordCategory, WordList};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// ============================================================================
+// Word List Validation - shared by the `wordlint` binary, pack uploads and tests
+// ============================================================================
+
+/// Longest word that still fits on a card without wrapping
+pub const MAX_WORD_LEN: usize = 24;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    /// The list can't be played as-is
+    Error,
+    /// Playable, but probably a mistake
+    Warning,
+}
+
+/// One problem found in a word list
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line in the source file, when it could be located
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, severity, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Whether any of the diagnostics make the list unplayable
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Line numbers of each category, its icon and its pairs in a `words.yaml`-style file.
+/// serde_yaml doesn't keep spans, so this follows the block layout the word files use.
+#[derive(Default)]
+struct LineMap {
+    categories: Vec<CategoryLines>,
+}
+
+#[derive(Default)]
+struct CategoryLines {
+    name: usize,
+    icon: Option<usize>,
+    pairs: Vec<usize>,
+}
+
+impl LineMap {
+    fn scan(source: &str) -> Self {
+        let mut map = LineMap::default();
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("- name:") {
+                map.categories.push(CategoryLines { name: line_number, ..Default::default() });
+            } else if let Some(category) = map.categories.last_mut() {
+                if trimmed.starts_with("icon:") {
+                    category.icon = Some(line_number);
+                } else if trimmed.starts_with("- [") {
+                    category.pairs.push(line_number);
+                }
+            }
+        }
+        map
+    }
+
+    fn category(&self, category: usize) -> Option<usize> {
+        self.categories.get(category).map(|c| c.name)
+    }
+
+    fn icon(&self, category: usize) -> Option<usize> {
+        let lines = self.categories.get(category)?;
+        lines.icon.or(Some(lines.name))
+    }
+
+    fn pair(&self, category: usize, pair: usize) -> Option<usize> {
+        self.categories.get(category)?.pairs.get(pair).copied()
+    }
+}
+
+/// Lowercase, dash-separated identifier safe to use inside an ID
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// A category's ID within its pack: its explicit `id` if it has one, otherwise its name, slugged
+pub fn category_slug(category: &WordCategory) -> String {
+    let local_id = if category.id.trim().is_empty() { &category.name } else { &category.id };
+    slugify(local_id)
+}
+
+/// Parse and check a `words.yaml`-style file, pointing at the offending lines
+pub fn validate_words_yaml(source: &str) -> Vec<Diagnostic> {
+    match serde_yaml::from_str::<WordList>(source) {
+        Ok(word_list) => check(&word_list, &LineMap::scan(source)),
+        Err(e) => vec![Diagnostic {
+            severity: Severity::Error,
+            line: e.location().map(|l| l.line()),
+            message: format!("could not parse word list: {}", e),
+        }],
+    }
+}
+
+/// Check an already parsed word list (e.g. one loaded from JSON), without line numbers
+pub fn validate_word_list(word_list: &WordList) -> Vec<Diagnostic> {
+    check(word_list, &LineMap::default())
+}
+
+fn check(word_list: &WordList, lines: &LineMap) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut push = |severity, line, message| diagnostics.push(Diagnostic { severity, line, message });
+
+    if word_list.categories.is_empty() {
+        push(Severity::Error, None, "word list has no categories".to_string());
+    }
+
+    // Lowercased pair -> (category, pair) where it was first seen
+    let mut seen_pairs: HashMap<(String, String), (usize, usize)> = HashMap::new();
+    // Category ID -> category that has it, so two categories can't end up sharing an ID
+    let mut seen_ids: HashMap<String, usize> = HashMap::new();
+
+    for (c, category) in word_list.categories.iter().enumerate() {
+        let name = category.name.trim();
+        if name.is_empty() {
+            push(Severity::Error, lines.category(c), format!("category {} has no name", c + 1));
+        }
+        if category.icon.trim().is_empty() {
+            push(Severity::Error, lines.icon(c), format!("category \"{}\" has no icon", name));
+        }
+        if category.pairs.is_empty() {
+            push(Severity::Error, lines.category(c), format!("category \"{}\" has no word pairs", name));
+        }
+        let id = category_slug(category);
+        match seen_ids.get(&id) {
+            Some(&first_c) => push(
+                Severity::Error,
+                lines.category(c),
+                format!(
+                    "category \"{}\" has the same ID \"{}\" as \"{}\"",
+                    name, id, word_list.categories[first_c].name
+                ),
+            ),
+            None => {
+                seen_ids.insert(id, c);
+            }
+        }
+
+        for (p, (first, second)) in category.pairs.iter().enumerate() {
+            let line = lines.pair(c, p);
+            let (a, b) = (first.trim().to_lowercase(), second.trim().to_lowercase());
+
+            if a.is_empty() || b.is_empty() {
+                push(Severity::Error, line, format!("pair [\"{}\", \"{}\"] has an empty word", first, second));
+                continue;
+            }
+            if a == b {
+                push(Severity::Error, line, format!("pair [\"{}\", \"{}\"] uses the same word twice", first, second));
+                continue;
+            }
+            for word in [first, second] {
+                if word.chars().count() > MAX_WORD_LEN {
+                    push(
+                        Severity::Warning,
+                        line,
+                        format!("\"{}\" is longer than {} characters", word, MAX_WORD_LEN),
+                    );
+                }
+            }
+
+            // The same two words in either order count as the same pair
+            let key = if a <= b { (a, b) } else { (b, a) };
+            match seen_pairs.get(&key) {
+                Some(&(first_c, first_p)) if first_c == c => push(
+                    Severity::Error,
+                    line,
+                    format!(
+                        "pair [\"{}\", \"{}\"] is repeated in \"{}\" (first at pair {})",
+                        first, second, name, first_p + 1
+                    ),
+                ),
+                Some(&(first_c, _)) => push(
+                    Severity::Warning,
+                    line,
+                    format!(
+                        "pair [\"{}\", \"{}\"] is also in \"{}\"",
+                        first, second, word_list.categories[first_c].name
+                    ),
+                ),
+                None => {
+                    seen_pairs.insert(key, (c, p));
+                }
+            }
+        }
+
+        // Synonyms must belong to one of the category's words, and can't be another word in it
+        let words: HashSet<String> = category
+            .pairs
+            .iter()
+            .flat_map(|(first, second)| [first, second])
+            .map(|word| word.trim().to_lowercase())
+            .collect();
+        for (word, alternatives) in &category.synonyms {
+            if !words.contains(&word.trim().to_lowercase()) {
+                push(
+                    Severity::Warning,
+                    lines.category(c),
+                    format!("synonyms for \"{}\" don't match any word in \"{}\"", word, name),
+                );
+            }
+            for alternative in alternatives {
+                if words.contains(&alternative.trim().to_lowercase()) {
+                    push(
+                        Severity::Warning,
+                        lines.category(c),
+                        format!("\"{}\" is a synonym for \"{}\" but also a word in \"{}\"", alternative, word, name),
+                    );
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        validate_words_yaml(source).iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn embedded_words_yaml_is_valid() {
+        let diagnostics = validate_words_yaml(include_str!("../../../words.yaml"));
+        let errors: Vec<String> = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(|d| d.to_string())
+            .collect();
+        assert!(errors.is_empty(), "words.yaml has errors:\n{}", errors.join("\n"));
+    }
+
+    #[test]
+    fn reports_parse_errors_with_line() {
+        let diagnostics = validate_words_yaml("categories:\n  - name: \"A\"\n    pairs: [\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].line.is_some());
+    }
+
+    #[test]
+    fn reports_empty_category_and_missing_icon() {
+        let source = "categories:\n  - name: \"Empty\"\n    icon: \"\"\n    pairs: []\n";
+        assert_eq!(
+            messages(source),
+            vec![
+                "line 3: error: category \"Empty\" has no icon",
+                "line 2: error: category \"Empty\" has no word pairs",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_same_word_and_duplicate_pairs() {
+        let source = "\
+categories:
+  - name: \"A\"
+    icon: \"🅰️\"
+    pairs:
+      - [\"Cat\", \"Dog\"]
+      - [\"Tea\", \"tea\"]
+      - [\"dog\", \"CAT\"]
+  - name: \"B\"
+    icon: \"🅱️\"
+    pairs:
+      - [\"Cat\", \"Dog\"]
+";
+        assert_eq!(
+            messages(source),
+            vec![
+                "line 6: error: pair [\"Tea\", \"tea\"] uses the same word twice",
+                "line 7: error: pair [\"dog\", \"CAT\"] is repeated in \"A\" (first at pair 1)",
+                "line 11: warning: pair [\"Cat\", \"Dog\"] is also in \"A\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_categories_sharing_an_id() {
+        let source = "\
+categories:
+  - name: \"Food & Drinks\"
+    icon: \"🍕\"
+    pairs:
+      - [\"Tea\", \"Coffee\"]
+  - name: \"Food - Drinks\"
+    icon: \"🥤\"
+    pairs:
+      - [\"Juice\", \"Soda\"]
+  - name: \"Snacks\"
+    id: \"food-drinks\"
+    icon: \"🍿\"
+    pairs:
+      - [\"Chips\", \"Nuts\"]
+";
+        assert_eq!(
+            messages(source),
+            vec![
+                "line 6: error: category \"Food - Drinks\" has the same ID \"food-drinks\" as \"Food & Drinks\"",
+                "line 10: error: category \"Snacks\" has the same ID \"food-drinks\" as \"Food & Drinks\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_long_words() {
+        let source = "categories:\n  - name: \"A\"\n    icon: \"🅰️\"\n    pairs:\n      - [\"Supercalifragilisticexpialidocious\", \"Word\"]\n";
+        let diagnostics = validate_words_yaml(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].line, Some(5));
+    }
+
+    #[test]
+    fn warns_about_synonyms_that_do_not_fit() {
+        let source = "categories:\n  - name: \"A\"\n    icon: \"🅰️\"\n    pairs:\n      - [\"Car\", \"Van\"]\n    synonyms:\n      Car: [\"Automobile\", \"Van\"]\n      Bus: [\"Coach\"]\n";
+        assert_eq!(
+            messages(source),
+            vec![
+                "line 2: warning: synonyms for \"Bus\" don't match any word in \"A\"",
+                "line 2: warning: \"Van\" is a synonym for \"Car\" but also a word in \"A\"",
+            ]
+        );
+    }
+}
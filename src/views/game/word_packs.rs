@@ -0,0 +1,331 @@
+use crate::views::game::types::{WordCategory, WordList, WordPack};
+use crate::views::game::validation::{
+    category_slug, has_errors, slugify, validate_word_list, validate_words_yaml, Severity,
+};
+use dioxus::prelude::*;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+// ============================================================================
+// Word Packs - extra categories loaded at runtime
+// ============================================================================
+
+/// Pack ID of the categories embedded from `words.yaml`
+pub const BUILTIN_PACK_ID: &str = "builtin";
+
+/// localStorage key holding the packs uploaded in this browser
+#[cfg(target_arch = "wasm32")]
+const LOCAL_PACKS_KEY: &str = "ultimate_imposter_word_packs";
+
+/// Environment variable pointing the server at a directory of pack files
+#[cfg(feature = "server")]
+pub const WORD_PACKS_DIR_ENV: &str = "ULTIMATE_IMPOSTER_WORD_PACKS_DIR";
+
+/// Used when `WORD_PACKS_DIR_ENV` isn't set
+#[cfg(feature = "server")]
+const DEFAULT_WORD_PACKS_DIR: &str = "word_packs";
+
+/// Packs served by the server. On the server build this is read from disk once;
+/// in the browser it is filled in by `refresh_server_word_packs`.
+static SERVER_PACKS: Lazy<Mutex<Vec<WordPack>>> = Lazy::new(|| Mutex::new(initial_server_packs()));
+
+/// Give every category in the pack its namespaced ID.
+/// Names are used when no explicit ID is given, so reordering or inserting categories keeps IDs stable.
+fn namespace_categories(pack_id: &str, categories: &mut [WordCategory]) {
+    for category in categories.iter_mut() {
+        category.id = format!("{}/{}", pack_id, category_slug(category));
+        category.pack_id = pack_id.to_string();
+    }
+}
+
+/// Wrap the categories from `words.yaml` as the built-in pack
+pub fn builtin_pack(word_list: WordList) -> WordPack {
+    let mut categories = word_list.categories;
+    namespace_categories(BUILTIN_PACK_ID, &mut categories);
+    WordPack {
+        id: BUILTIN_PACK_ID.to_string(),
+        name: "Built-in".to_string(),
+        categories,
+    }
+}
+
+/// Pack file layout: `words.yaml` plus an optional display name
+#[derive(serde::Deserialize)]
+struct WordPackFile {
+    name: Option<String>,
+    categories: Vec<WordCategory>,
+}
+
+/// Parse an uploaded or on-disk pack. JSON is picked by extension, anything else is read as YAML.
+pub fn parse_word_pack(file_name: &str, contents: &str) -> Result<WordPack, String> {
+    let stem = file_name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(file_name);
+    let is_json = file_name.to_ascii_lowercase().ends_with(".json");
+    let file: WordPackFile = if is_json {
+        serde_json::from_str(contents).map_err(|e| format!("{}: {}", file_name, e))?
+    } else {
+        serde_yaml::from_str(contents).map_err(|e| format!("{}: {}", file_name, e))?
+    };
+
+    // Same checks as `wordlint`; warnings are let through
+    let diagnostics = if is_json {
+        validate_word_list(&WordList { categories: file.categories.clone() })
+    } else {
+        validate_words_yaml(contents)
+    };
+    if has_errors(&diagnostics) {
+        let errors: Vec<String> = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(|d| d.to_string())
+            .collect();
+        return Err(format!("{}: {}", file_name, errors.join("; ")));
+    }
+
+    let name = file.name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| stem.to_string());
+    let id = slugify(&name);
+    if id.is_empty() {
+        return Err(format!("{}: pack needs a name", file_name));
+    }
+    if id == BUILTIN_PACK_ID {
+        return Err(format!("{}: \"{}\" is reserved", file_name, BUILTIN_PACK_ID));
+    }
+
+    let mut categories = file.categories;
+    namespace_categories(&id, &mut categories);
+    Ok(WordPack { id, name, categories })
+}
+
+// ============================================================================
+// Browser packs (localStorage)
+// ============================================================================
+
+/// Packs uploaded in this browser
+pub fn load_local_word_packs() -> Vec<WordPack> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        let stored = window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(LOCAL_PACKS_KEY).ok().flatten());
+        stored
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Vec::new()
+    }
+}
+
+fn store_local_word_packs(_packs: &[WordPack]) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::window;
+
+        if let Some(window) = window() {
+            if let Ok(Some(storage)) = window.local_storage() {
+                if let Ok(json) = serde_json::to_string(_packs) {
+                    let _ = storage.set_item(LOCAL_PACKS_KEY, &json);
+                }
+            }
+        }
+    }
+}
+
+/// Keep an uploaded pack in this browser, replacing any pack with the same ID
+pub fn save_local_word_pack(pack: WordPack) {
+    let mut packs = load_local_word_packs();
+    packs.retain(|p| p.id != pack.id);
+    packs.push(pack);
+    store_local_word_packs(&packs);
+}
+
+/// Forget an uploaded pack
+pub fn remove_local_word_pack(pack_id: &str) {
+    let mut packs = load_local_word_packs();
+    packs.retain(|p| p.id != pack_id);
+    store_local_word_packs(&packs);
+}
+
+// ============================================================================
+// Server packs (directory of .yaml / .yml / .json files)
+// ============================================================================
+
+#[cfg(feature = "server")]
+fn initial_server_packs() -> Vec<WordPack> {
+    let dir = std::env::var_os(WORD_PACKS_DIR_ENV)
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from(DEFAULT_WORD_PACKS_DIR));
+    read_word_pack_dir(&dir)
+}
+
+/// Every pack file in a directory, in file name order. Files that don't parse, and packs
+/// with the same ID as one already read, are skipped with a warning.
+#[cfg(feature = "server")]
+fn read_word_pack_dir(dir: &std::path::Path) -> Vec<WordPack> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+
+    let mut packs: Vec<WordPack> = Vec::new();
+    for path in paths {
+        let is_pack = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml") | Some("yml") | Some("json")
+        );
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !is_pack {
+            continue;
+        }
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|c| parse_word_pack(file_name, &c)) {
+            Ok(pack) if packs.iter().all(|p| p.id != pack.id) => packs.push(pack),
+            Ok(pack) => dioxus::logger::tracing::warn!("Skipping duplicate word pack {}", pack.id),
+            Err(e) => dioxus::logger::tracing::warn!("Skipping word pack: {}", e),
+        }
+    }
+    packs
+}
+
+#[cfg(not(feature = "server"))]
+fn initial_server_packs() -> Vec<WordPack> {
+    Vec::new()
+}
+
+/// Packs read from the server's pack directory
+pub fn server_word_packs() -> Vec<WordPack> {
+    SERVER_PACKS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The server's pack directory, as seen by the server
+#[get("/api/word_packs")]
+pub async fn list_server_word_packs() -> Result<Vec<WordPack>, ServerFnError> {
+    Ok(server_word_packs())
+}
+
+/// Pull the server's packs into this client. Quietly keeps what it had when there is no server.
+pub async fn refresh_server_word_packs() {
+    if let Ok(packs) = list_server_word_packs().await {
+        *SERVER_PACKS.lock().unwrap_or_else(|e| e.into_inner()) = packs;
+    }
+}
+
+/// Every extra pack available on this device: server packs first, then browser uploads
+pub fn extra_word_packs() -> Vec<WordPack> {
+    merge_word_packs(server_word_packs(), load_local_word_packs())
+}
+
+/// Add browser uploads after the server's packs. An upload can't stand in for a server pack with the same ID.
+fn merge_word_packs(mut packs: Vec<WordPack>, local: Vec<WordPack>) -> Vec<WordPack> {
+    for pack in local {
+        if packs.iter().all(|p| p.id != pack.id) {
+            packs.push(pack);
+        }
+    }
+    packs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::game::utils::get_all_word_packs;
+    use std::collections::HashSet;
+
+    const PARTY_YAML: &str = "\
+name: \"Party Night\"
+categories:
+  - name: \"Food & Drinks\"
+    icon: \"🥤\"
+    pairs:
+      - [\"Punch\", \"Lemonade\"]
+  - name: \"Games\"
+    id: \"board-games\"
+    icon: \"🎲\"
+    pairs:
+      - [\"Chess\", \"Checkers\"]
+";
+
+    #[test]
+    fn parses_a_pack_and_namespaces_its_categories() {
+        let pack = parse_word_pack("party.yaml", PARTY_YAML).unwrap();
+        assert_eq!((pack.id.as_str(), pack.name.as_str()), ("party-night", "Party Night"));
+        let ids: Vec<&str> = pack.categories.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["party-night/food-drinks", "party-night/board-games"]);
+        assert!(pack.categories.iter().all(|c| c.pack_id == "party-night"));
+
+        // JSON works the same, and without a name the file name stands in
+        let json = r#"{"categories": [{"name": "Games", "icon": "🎲", "pairs": [["Chess", "Checkers"]]}]}"#;
+        let pack = parse_word_pack("Game Night.JSON", json).unwrap();
+        assert_eq!((pack.id.as_str(), pack.name.as_str()), ("game-night", "Game Night"));
+        assert_eq!(pack.categories[0].id, "game-night/games");
+    }
+
+    #[test]
+    fn rejects_packs_that_cannot_be_played() {
+        let bad_yaml = parse_word_pack("broken.yaml", "categories: [\n").unwrap_err();
+        assert!(bad_yaml.starts_with("broken.yaml: "), "{}", bad_yaml);
+        assert!(parse_word_pack("broken.json", "{").is_err());
+
+        let clashing = "\
+categories:
+  - name: \"Games\"
+    icon: \"🎲\"
+    pairs:
+      - [\"Chess\", \"Checkers\"]
+  - name: \"Other\"
+    id: \"games\"
+    icon: \"🃏\"
+    pairs:
+      - [\"Poker\", \"Bridge\"]
+";
+        let error = parse_word_pack("clash.yaml", clashing).unwrap_err();
+        assert!(error.contains("same ID \"games\""), "{}", error);
+
+        let reserved = PARTY_YAML.replace("Party Night", "builtin");
+        assert!(parse_word_pack("mine.yaml", &reserved).unwrap_err().contains("reserved"));
+        let nameless = PARTY_YAML.replace("Party Night", "!!!");
+        assert!(parse_word_pack("!!!.yaml", &nameless).unwrap_err().contains("needs a name"));
+    }
+
+    #[test]
+    fn packs_merge_with_the_built_in_words() {
+        let party = parse_word_pack("party.yaml", PARTY_YAML).unwrap();
+        let mut upload = parse_word_pack("upload.yaml", PARTY_YAML).unwrap();
+        upload.name = "My copy".to_string();
+        let other = parse_word_pack("other.yaml", &PARTY_YAML.replace("Party Night", "Other")).unwrap();
+
+        // A server pack keeps its place over an upload with the same ID
+        let merged = merge_word_packs(vec![party.clone()], vec![upload, other]);
+        let names: Vec<&str> = merged.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Party Night", "Other"]);
+
+        // A pack's category can share a built-in category's name without sharing its ID
+        let mut packs = get_all_word_packs();
+        packs.extend(merged);
+        let ids: Vec<&str> = packs.iter().flat_map(|p| p.categories.iter().map(|c| c.id.as_str())).collect();
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+        assert!(ids.contains(&"builtin/food-drinks") && ids.contains(&"party-night/food-drinks"));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn a_pack_directory_skips_duplicates_and_bad_files() {
+        let dir = std::env::temp_dir().join(format!("ultimate-imposter-packs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a-party.yaml"), PARTY_YAML).unwrap();
+        std::fs::write(dir.join("b-party.yml"), PARTY_YAML).unwrap();
+        std::fs::write(dir.join("c-broken.yaml"), "categories: [\n").unwrap();
+        std::fs::write(dir.join("d-notes.txt"), "not a pack").unwrap();
+        std::fs::write(dir.join("e-games.json"), r#"{"categories": [{"name": "Games", "icon": "🎲", "pairs": [["Chess", "Checkers"]]}]}"#).unwrap();
+
+        let ids: Vec<String> = read_word_pack_dir(&dir).into_iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec!["party-night", "e-games"]);
+        assert!(read_word_pack_dir(&dir.join("missing")).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,7 @@
 //! The views module contains the components for all Routes for our app.
 
 mod game;
-pub use game::Game;
+pub use game::{Game, RoomHost, RoomJoin, SavedGames, Stats};
+
+#[cfg(feature = "server")]
+pub use game::{seat_index, GameAction, GameEngine, GameError, GameState, RoomTicket};